@@ -6,21 +6,25 @@
 //  @author hanepjiv <hanepjiv@gmail.com>
 //  @copyright The MIT License (MIT) / Apache License Version 2.0
 //  @since 2016/10/21
-//  @date 2025/04/06
+//  @date 2026/10/18
 
 // use  =======================================================================
 use std::io::Write as _;
 // ----------------------------------------------------------------------------
 use crate::error::Error;
 // ////////////////////////////////////////////////////////////////////////////
+/// ask
+///
+/// Prompts on the standard error, keeping the standard output for reports
+/// and diffs.
 pub(crate) fn ask(msg: &str, default: bool) -> Result<bool, Error> {
-    std::io::stdout().write_all(msg.as_ref())?;
-    std::io::stdout().write_all(if default {
+    std::io::stderr().write_all(msg.as_ref())?;
+    std::io::stderr().write_all(if default {
         b" [Y/n]: "
     } else {
         b" [y/N]: "
     })?;
-    std::io::stdout().flush()?;
+    std::io::stderr().flush()?;
     let mut line = String::new();
     let _ = std::io::stdin().read_line(&mut line)?;
     match line.trim().to_lowercase().as_str() {
@@ -0,0 +1,125 @@
+// -*- coding:utf-8-unix; -*-
+
+//! backup.rs
+
+//  Copyright 2026 hanepjiv
+//  @author hanepjiv <hanepjiv@gmail.com>
+//  @copyright The MIT License (MIT) / Apache License Version 2.0
+//  @since 2026/10/18
+//  @date 2026/10/18
+
+// ////////////////////////////////////////////////////////////////////////////
+// use  =======================================================================
+use std::{
+    ffi::OsString,
+    path::{Component, Path, PathBuf},
+};
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+/// enum Backup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Backup {
+    ///  Unknown.
+    Unknown,
+    ///  None, no backup.
+    None,
+    ///  Sibling, next to the replaced file.
+    Sibling,
+    ///  Directory, mirrored under `backup_dir`.
+    Directory,
+}
+// ============================================================================
+impl<'a> From<&'a str> for Backup {
+    // ========================================================================
+    #[inline]
+    fn from(src: &'a str) -> Self {
+        match src.to_lowercase().as_str() {
+            "none" => Self::None,
+            "sibling" => Self::Sibling,
+            "directory" => Self::Directory,
+            _ => Self::Unknown,
+        }
+    }
+}
+// ============================================================================
+impl Backup {
+    // ========================================================================
+    /// path
+    ///
+    /// `suffix` is appended to the whole file name, so `Makefile` becomes
+    /// `Makefile.backup`.  `Directory` mirrors `relative`, the path of
+    /// `path` relative to the project root, normalized under `dir`.
+    pub(crate) fn path(
+        self,
+        path: &Path,
+        relative: &Path,
+        dir: &Path,
+        suffix: &str,
+    ) -> Option<PathBuf> {
+        let mut name = OsString::from(path.file_name()?);
+        name.push(suffix);
+        match self {
+            Self::Unknown | Self::None => None,
+            Self::Sibling => Some(path.with_file_name(name)),
+            Self::Directory => {
+                let mut ret = PathBuf::new();
+                for i in relative.parent()?.components() {
+                    match i {
+                        Component::Normal(x) => ret.push(x),
+                        Component::ParentDir => {
+                            let _ = ret.pop();
+                        }
+                        Component::Prefix(_)
+                        | Component::RootDir
+                        | Component::CurDir => {}
+                    }
+                }
+                ret.push(name);
+                Some(dir.join(ret))
+            }
+        }
+    }
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    // use  ===================================================================
+    use std::path::{Path, PathBuf};
+    // ------------------------------------------------------------------------
+    use super::Backup;
+    // ========================================================================
+    #[test]
+    fn path() {
+        let dir = Path::new("/p/.column79.backup");
+        let path = |backup: Backup, path: &str, suffix: &str| {
+            backup.path(Path::new(path), Path::new(path), dir, suffix)
+        };
+        assert_eq!(
+            path(Backup::Sibling, "src/lib.rs", ".backup"),
+            Some(PathBuf::from("src/lib.rs.backup"))
+        );
+        assert_eq!(
+            path(Backup::Sibling, "Makefile", ".orig"),
+            Some(PathBuf::from("Makefile.orig"))
+        );
+        assert_eq!(
+            path(Backup::Directory, "./a/Dockerfile", "~"),
+            Some(PathBuf::from("/p/.column79.backup/a/Dockerfile~"))
+        );
+        assert_eq!(
+            path(Backup::Directory, "/x/../y.c", ""),
+            Some(PathBuf::from("/p/.column79.backup/y.c"))
+        );
+        assert_eq!(
+            Backup::Directory.path(
+                Path::new("../a.c"),
+                Path::new("src/a.c"),
+                dir,
+                ""
+            ),
+            Some(PathBuf::from("/p/.column79.backup/src/a.c"))
+        );
+        assert_eq!(path(Backup::None, "a.c", ""), None);
+    }
+}
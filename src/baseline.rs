@@ -0,0 +1,129 @@
+// -*- coding:utf-8-unix; -*-
+
+//! baseline.rs
+
+//  Copyright 2026 hanepjiv
+//  @author hanepjiv <hanepjiv@gmail.com>
+//  @copyright The MIT License (MIT) / Apache License Version 2.0
+//  @since 2026/10/18
+//  @date 2026/10/18
+
+// ////////////////////////////////////////////////////////////////////////////
+// use  =======================================================================
+use alloc::collections::BTreeMap;
+use std::{fs::File, io::Write as _, path::Path};
+// ----------------------------------------------------------------------------
+use serde::{Deserialize, Serialize};
+// ----------------------------------------------------------------------------
+use crate::error::Error;
+// ////////////////////////////////////////////////////////////////////////////
+// define  ====================================================================
+const VERSION: usize = 1;
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+/// struct Baseline.
+///
+/// Known violations, counted per path and per content hash of the line, so
+/// that they survive lines moving around the file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Baseline {
+    /// version
+    version: usize,
+    /// files
+    files: BTreeMap<String, BTreeMap<String, usize>>,
+}
+// ============================================================================
+impl Baseline {
+    // ========================================================================
+    /// new
+    pub(crate) const fn new() -> Self {
+        Self {
+            version: VERSION,
+            files: BTreeMap::new(),
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// load
+    pub(crate) fn load(path: &Path) -> Result<Self, Error> {
+        let ret: Self = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        if VERSION != ret.version {
+            return Err(Error::InvalidConfig(format!(
+                "::column79::baseline::Baseline::load: \
+                 path = \"{0}\": invalid version {1}",
+                path.display(),
+                ret.version
+            )));
+        }
+        Ok(ret)
+    }
+    // ------------------------------------------------------------------------
+    /// save
+    pub(crate) fn save(&self, path: &Path) -> Result<(), Error> {
+        let mut file = File::create(path)?;
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.write_all(b"\n")?;
+        Ok(())
+    }
+    // ========================================================================
+    /// insert
+    pub(crate) fn insert(&mut self, path: &str, line: &str) {
+        let count = self
+            .files
+            .entry(path.to_owned())
+            .or_default()
+            .entry(Self::hash(line))
+            .or_insert(0);
+        *count = count.saturating_add(1);
+    }
+    // ------------------------------------------------------------------------
+    /// take
+    ///
+    /// Consumes one known violation of `line` in `path`, if any.
+    pub(crate) fn take(&mut self, path: &str, line: &str) -> bool {
+        self.files
+            .get_mut(path)
+            .and_then(|x| x.get_mut(&Self::hash(line)))
+            .and_then(|count| {
+                let rest = count.checked_sub(1)?;
+                *count = rest;
+                Some(())
+            })
+            .is_some()
+    }
+    // ------------------------------------------------------------------------
+    /// hash, 64-bit FNV-1a.
+    fn hash(line: &str) -> String {
+        let ret = line.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+            (h ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+        format!("{ret:016x}")
+    }
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    // use  ===================================================================
+    use super::Baseline;
+    // ========================================================================
+    #[test]
+    fn hash() {
+        assert_eq!(Baseline::hash(""), "cbf29ce484222325");
+        assert_eq!(Baseline::hash("a"), "af63dc4c8601ec8c");
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn take() {
+        let mut baseline = Baseline::new();
+        baseline.insert("a.rs", "x");
+        baseline.insert("a.rs", "x");
+        let json = serde_json::to_string(&baseline).expect("to_string");
+        let mut baseline: Baseline =
+            serde_json::from_str(&json).expect("from_str");
+        assert!(!baseline.take("b.rs", "x"));
+        assert!(!baseline.take("a.rs", "y"));
+        assert!(baseline.take("a.rs", "x"));
+        assert!(baseline.take("a.rs", "x"));
+        assert!(!baseline.take("a.rs", "x"));
+    }
+}
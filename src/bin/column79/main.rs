@@ -6,7 +6,7 @@
 //  @author hanepjiv <hanepjiv@gmail.com>
 //  @copyright The MIT License (MIT) / Apache License Version 2.0
 //  @since 2016/10/12
-//  @date 2026/05/30
+//  @date 2026/10/18
 
 // ////////////////////////////////////////////////////////////////////////////
 // attribute  =================================================================
@@ -15,19 +15,24 @@
 // mod  =======================================================================
 pub(crate) mod error;
 // use  =======================================================================
-use std::{env, path::PathBuf};
+use std::{env, fs, io, path::PathBuf, process::ExitCode};
 // ----------------------------------------------------------------------------
 use bitflags as _;
 use dirs as _;
+use globset as _;
+use ignore as _;
 use log as _;
 use regex as _;
 use serde as _;
+use serde_json as _;
+use similar as _;
 use tempfile as _;
 use toml as _;
 use unicode_segmentation as _;
+use unicode_width as _;
 // ----------------------------------------------------------------------------
 use self::error::{Error, Result};
-use column79::{Column79, Command, Flags};
+use column79::{Column79, Command, Flags, Options};
 // ////////////////////////////////////////////////////////////////////////////
 // ============================================================================
 fn print_usage(opts: &::getopts::Options) {
@@ -35,7 +40,7 @@ fn print_usage(opts: &::getopts::Options) {
         "{}",
         opts.usage(&format!(
             "Usage:
-    {0} Command [Input] [Options]
+    {0} Command [Input...] [Options]
 
 Command:
     init        initialize the configure
@@ -43,15 +48,44 @@ Command:
     replace     replace the result of the checked
 
 Input:
-    ./          current directory (default)",
+    ./          current directory (default)
+    PATH        files and directories to check
+    -           the standard input, named by --stdin-filename
+
+Exit status:
+    0           no violations found
+    1           check found violations
+    2           configuration or IO error",
             module_path!()
         ))
     );
 }
 // ============================================================================
-fn main() -> Result<()> {
+/// `EXIT_VIOLATION`
+const EXIT_VIOLATION: u8 = 1;
+/// `EXIT_ERROR`
+const EXIT_ERROR: u8 = 2;
+// ============================================================================
+fn main() -> ExitCode {
     env_logger::init();
 
+    exit_code(run())
+}
+// ============================================================================
+/// `exit_code`
+///
+/// Reports an error of `result` and maps it to `EXIT_ERROR`.
+fn exit_code(result: Result<ExitCode>) -> ExitCode {
+    match result {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("Error: {e:?}");
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+// ============================================================================
+fn run() -> Result<ExitCode> {
     let mut opts = ::getopts::Options::new();
     let _ = opts
         .optflag("v", "version", "print version")
@@ -64,37 +98,129 @@ fn main() -> Result<()> {
             "set language LANG=('cargo'|'rust'|'c'|'c++'|...)",
             "LANG",
         )
-        .optflag("", "no-ask", "will not be asked to allow");
+        .optopt("j", "jobs", "set number of threads, 0 for all cores", "NUM")
+        .optopt(
+            "f",
+            "format",
+            "set output format FORMAT=('text'|'json'|'jsonl'|'sarif')",
+            "FORMAT",
+        )
+        .optopt(
+            "",
+            "backup",
+            "set backup policy MODE=('none'|'sibling'|'directory'); \
+             a hard linked file, rewritten in place, is always backed up",
+            "MODE",
+        )
+        .optopt(
+            "",
+            "baseline",
+            "report only violations not recorded in FILE",
+            "FILE",
+        )
+        .optopt(
+            "",
+            "write-baseline",
+            "record the violations to FILE instead of reporting them",
+            "FILE",
+        )
+        .optopt(
+            "",
+            "changed-since",
+            "check only rows changed since the git revision REV, and \
+             untracked files",
+            "REV",
+        )
+        .optflag("", "staged", "check only rows changed in the git index")
+        .optopt(
+            "",
+            "files-from",
+            "read input paths from FILE, one per line, '-' for stdin",
+            "FILE",
+        )
+        .optopt(
+            "",
+            "stdin-filename",
+            "check the standard input as PATH to choose the language",
+            "PATH",
+        )
+        .optmulti(
+            "",
+            "include",
+            "check only files matching GLOB, relative to the project root",
+            "GLOB",
+        )
+        .optmulti(
+            "",
+            "exclude",
+            "skip files matching GLOB, relative to the project root",
+            "GLOB",
+        )
+        .optflag("", "no-ask", "will not be asked to allow")
+        .optflag(
+            "",
+            "dry-run",
+            "print a unified diff instead of replacing files, without asking",
+        )
+        .optflag("", "diff", "same as --dry-run")
+        .optflag(
+            "",
+            "keep-mtime",
+            "keep the modification time of replaced files",
+        )
+        .optflag(
+            "",
+            "no-ignore",
+            "do not respect .gitignore, .ignore and .column79ignore",
+        );
 
     let matches = opts.parse(env::args().skip(1))?;
     if matches.opt_present("v") {
         println!(concat!(module_path!(), " v", env!("CARGO_PKG_VERSION")));
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
     if matches.free.is_empty() || matches.opt_present("h") {
         print_usage(&opts);
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
 
     let command = if let Some(cmd) = matches.free.first() {
         Command::from(cmd.as_ref())
     } else {
         print_usage(&opts);
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     };
 
     if Command::Unknown == command {
         print_usage(&opts);
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
 
-    let input = if 1_usize == matches.free.len() {
-        ::std::env::current_dir()?
-    } else {
-        PathBuf::from(matches.free.get(1).ok_or_else(|| {
-            Error::OptionNone("column79: empty input".to_owned())
-        })?)
-    };
+    let mut inputs = matches
+        .free
+        .iter()
+        .skip(1)
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    if let Some(x) = matches.opt_str("files-from") {
+        let list = if "-" == x {
+            if inputs.iter().any(|i| i.as_os_str() == "-") {
+                return Err(Error::OptionNone(
+                    "column79: --files-from - with input -".to_owned(),
+                ));
+            }
+            io::read_to_string(io::stdin())?
+        } else {
+            fs::read_to_string(x)?
+        };
+        inputs.extend(
+            list.lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(PathBuf::from),
+        );
+    } else if inputs.is_empty() {
+        inputs.push(env::current_dir()?);
+    }
 
     let column = match matches.opt_str("c") {
         Some(x) => Some(x.parse::<usize>().map_err(|_e| {
@@ -110,15 +236,85 @@ fn main() -> Result<()> {
         None => None,
     };
 
+    let jobs = match matches.opt_str("j") {
+        Some(x) => Some(x.parse::<usize>().map_err(|_e| {
+            Error::OptionNone("column79: opt_str('j').".to_owned())
+        })?),
+        None => None,
+    };
+
     let language = matches.opt_str("l");
 
+    let format = matches.opt_str("f");
+
     let mut fs = Flags::empty();
 
     if matches.opt_present("no-ask") {
         fs.insert(Flags::NOASK);
     }
 
-    Column79::run(command, input, language, column, septhr, fs)?;
+    if matches.opt_present("dry-run") || matches.opt_present("diff") {
+        fs.insert(Flags::DRYRUN);
+    }
+
+    if matches.opt_present("keep-mtime") {
+        fs.insert(Flags::KEEPMTIME);
+    }
 
-    Ok(())
+    if matches.opt_present("staged") {
+        fs.insert(Flags::STAGED);
+    }
+
+    if matches.opt_present("no-ignore") {
+        fs.insert(Flags::NOIGNORE);
+    }
+
+    let options = Options {
+        language,
+        column,
+        separator_threshold: septhr,
+        format,
+        backup: matches.opt_str("backup"),
+        include: matches.opt_strs("include"),
+        exclude: matches.opt_strs("exclude"),
+        baseline: matches.opt_str("baseline").map(PathBuf::from),
+        write_baseline: matches.opt_str("write-baseline").map(PathBuf::from),
+        changed_since: matches.opt_str("changed-since"),
+        jobs,
+        stdin_filename: matches.opt_str("stdin-filename").map(PathBuf::from),
+        flags: fs,
+    };
+
+    let summary = Column79::run(command, inputs, options)?;
+
+    if Command::Check == command && 0 < summary.violations() {
+        return Ok(ExitCode::from(EXIT_VIOLATION));
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    // use  ===================================================================
+    use std::{io, process::ExitCode};
+    // ------------------------------------------------------------------------
+    use super::{EXIT_ERROR, EXIT_VIOLATION, exit_code};
+    use crate::Error;
+    // ========================================================================
+    #[test]
+    fn exit_code_error() {
+        assert_eq!(
+            exit_code(Ok(ExitCode::from(EXIT_VIOLATION))),
+            ExitCode::from(EXIT_VIOLATION)
+        );
+        for e in [
+            Error::from(column79::Error::InvalidConfig("config".to_owned())),
+            Error::from(column79::Error::IO(io::Error::other("io"))),
+            Error::from(io::Error::other("io")),
+        ] {
+            assert_eq!(exit_code(Err(e)), ExitCode::from(EXIT_ERROR));
+        }
+    }
 }
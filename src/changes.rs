@@ -0,0 +1,299 @@
+// -*- coding:utf-8-unix; -*-
+
+//! changes.rs
+
+//  Copyright 2026 hanepjiv
+//  @author hanepjiv <hanepjiv@gmail.com>
+//  @copyright The MIT License (MIT) / Apache License Version 2.0
+//  @since 2026/10/18
+//  @date 2026/10/18
+
+// ////////////////////////////////////////////////////////////////////////////
+// use  =======================================================================
+use alloc::collections::BTreeMap;
+use core::ops::Range;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+// ----------------------------------------------------------------------------
+use crate::{config::Config, error::Error, flags::Flags};
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+/// struct Changes.
+///
+/// Rows added or modified per file, read from `git diff` hunks.  Every row
+/// of an untracked file is changed.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Changes {
+    /// files
+    files: BTreeMap<PathBuf, Vec<Range<usize>>>,
+    /// root, the top level of the work tree.
+    root: PathBuf,
+    /// staged, the rows are of the index, not of the work tree.
+    staged: bool,
+}
+// ============================================================================
+impl Changes {
+    // ========================================================================
+    /// new
+    ///
+    /// Diffs the work tree, or the index with `Flags::STAGED`, against
+    /// `Config::changed_since`, if either is given.  The files untracked
+    /// and not ignored count as new in the work tree.
+    pub(crate) fn new(
+        config: &Config,
+        input: &Path,
+    ) -> Result<Option<Self>, Error> {
+        let staged = config.flags.contains(Flags::STAGED);
+        if !staged && config.changed_since.is_none() {
+            return Ok(None);
+        }
+        let dir = if input.is_dir() {
+            input
+        } else {
+            input.parent().unwrap_or(input)
+        };
+        let dir = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+        let root = Self::git(dir, &["rev-parse", "--show-toplevel"])?;
+        let root = PathBuf::from(root.trim_end()).canonicalize()?;
+        let mut args = vec![
+            "-c",
+            "core.quotePath=off",
+            "diff",
+            "--no-color",
+            "--no-ext-diff",
+            "--unified=0",
+            "--src-prefix=a/",
+            "--dst-prefix=b/",
+        ];
+        if staged {
+            args.push("--cached");
+        }
+        if let Some(ref x) = config.changed_since {
+            args.push(x);
+        }
+        args.push("--");
+        let mut ret = Self::parse(&root, &Self::git(dir, &args)?);
+        ret.staged = staged;
+        if !staged {
+            let others = Self::git(
+                dir,
+                &[
+                    "-c",
+                    "core.quotePath=off",
+                    "ls-files",
+                    "--others",
+                    "--exclude-standard",
+                    "--full-name",
+                    ":/",
+                ],
+            )?;
+            for i in others.lines() {
+                let rows = ret.files.entry(root.join(i)).or_default();
+                rows.clear();
+                rows.push(1..usize::MAX);
+            }
+        }
+        ret.root = root;
+        Ok(Some(ret))
+    }
+    // ------------------------------------------------------------------------
+    /// git
+    fn git(dir: &Path, args: &[&str]) -> Result<String, Error> {
+        let output =
+            Command::new("git").arg("-C").arg(dir).args(args).output()?;
+        if !output.status.success() {
+            return Err(Error::Git(format!(
+                "::column79::changes::Changes::git: git {0}: {1}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            )));
+        }
+        String::from_utf8(output.stdout).map_err(|e| {
+            Error::Git(format!("::column79::changes::Changes::git: {e}"))
+        })
+    }
+    // ------------------------------------------------------------------------
+    /// parse
+    ///
+    /// A `+++ ` line is a file header only between `diff --git` and the
+    /// first hunk, a hunk may add a line starting with `++ `.
+    fn parse(root: &Path, diff: &str) -> Self {
+        let mut files = BTreeMap::<PathBuf, Vec<Range<usize>>>::new();
+        let mut current = None;
+        let mut header = false;
+        for line in diff.lines() {
+            if line.starts_with("diff --git ") {
+                header = true;
+                current = None;
+            } else if header && let Some(x) = line.strip_prefix("+++ ") {
+                current = x.strip_prefix("b/").map(|x| root.join(x));
+                if let Some(ref x) = current {
+                    let _ = files.entry(x.clone()).or_default();
+                }
+            } else if let Some(range) = Self::hunk(line) {
+                header = false;
+                if let Some(ref x) = current
+                    && !range.is_empty()
+                {
+                    files.entry(x.clone()).or_default().push(range);
+                }
+            }
+        }
+        Self {
+            files,
+            ..Self::default()
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// hunk
+    ///
+    /// The new rows of a hunk header `@@ -a,b +c,d @@`.
+    fn hunk(line: &str) -> Option<Range<usize>> {
+        let new = line
+            .strip_prefix("@@ -")?
+            .split(' ')
+            .nth(1)?
+            .strip_prefix('+')?;
+        let (start, len) = match new.split_once(',') {
+            Some((start, len)) => (start.parse().ok()?, len.parse().ok()?),
+            None => (new.parse().ok()?, 1),
+        };
+        Some(start..start.checked_add(len)?)
+    }
+    // ========================================================================
+    /// rows
+    ///
+    /// The changed rows of `path`, or `None` if it has not changed.
+    pub(crate) fn rows(&self, path: &Path) -> Option<&[Range<usize>]> {
+        let path = path.canonicalize().ok()?;
+        self.files.get(&path).map(Vec::as_slice)
+    }
+    // ------------------------------------------------------------------------
+    /// read
+    ///
+    /// The content of `path` the rows are of: the staged one in the index,
+    /// or the one in the work tree.
+    pub(crate) fn read(&self, path: &Path) -> Result<String, Error> {
+        if !self.staged {
+            return Ok(std::fs::read_to_string(path)?);
+        }
+        let path = path.canonicalize()?;
+        let name = path.strip_prefix(&self.root).map_err(|_| {
+            Error::Git(format!(
+                "::column79::changes::Changes::read: \
+                 {0:?} is outside the work tree",
+                path.display()
+            ))
+        })?;
+        let name = name
+            .components()
+            .map(|x| x.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        Self::git(&self.root, &["show", &format!(":{name}")])
+    }
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    // use  ===================================================================
+    use std::{path::Path, process::Command};
+    // ------------------------------------------------------------------------
+    use super::Changes;
+    use crate::{config::Config, flags::Flags};
+    // ========================================================================
+    #[test]
+    fn hunk() {
+        assert_eq!(Changes::hunk("@@ -1,2 +3,4 @@ fn f()"), Some(3..7));
+        assert_eq!(Changes::hunk("@@ -1 +3 @@"), Some(3..4));
+        assert_eq!(Changes::hunk("@@ -5,2 +4,0 @@"), Some(4..4));
+        assert_eq!(Changes::hunk("@@@ -1 -1 +1 @@@"), None);
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn parse() {
+        let diff = "\
+diff --git a/src/a.rs b/src/a.rs
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -1,0 +2,2 @@
++x
++y
+@@ -9 +10,0 @@
+-z
+@@ -20,0 +20 @@
++++ not a header
+@@ -30 +31 @@
+--- removed
++++ added
+@@ -40,0 +41 @@
++x
+diff --git a/b.rs b/b.rs
+--- a/b.rs
++++ /dev/null
+@@ -1 +0,0 @@
+-w
+";
+        let root = Path::new("/r");
+        let changes = Changes::parse(root, diff);
+        assert_eq!(changes.files.len(), 1);
+        assert_eq!(
+            changes
+                .files
+                .get(&root.join("src/a.rs"))
+                .map(|x| x.iter().flat_map(Clone::clone).collect::<Vec<_>>()),
+            Some(vec![2, 3, 20, 31, 41])
+        );
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn staged() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let git = |args: &[&str]| {
+            assert!(
+                Command::new("git")
+                    .arg("-C")
+                    .arg(dir.path())
+                    .args(["-c", "user.name=a", "-c", "user.email=a@a"])
+                    .args(args)
+                    .output()
+                    .expect("git")
+                    .status
+                    .success()
+            );
+        };
+        let path = dir.path().join("a.sh");
+        git(&["init", "-q"]);
+        std::fs::write(&path, "a\nb\n").expect("write");
+        git(&["add", "a.sh"]);
+        git(&["commit", "-q", "-m", "a"]);
+        std::fs::write(&path, "a\nstaged\n").expect("write");
+        git(&["add", "a.sh"]);
+        std::fs::write(&path, "work\nstaged\nwork\n").expect("write");
+        let mut config = Config::default();
+        config.flags.insert(Flags::STAGED);
+        let changes = Changes::new(&config, dir.path())
+            .expect("Changes::new")
+            .expect("staged");
+        assert_eq!(
+            changes
+                .rows(&path)
+                .map(|x| x.iter().flat_map(Clone::clone).collect::<Vec<_>>()),
+            Some(vec![2])
+        );
+        assert_eq!(changes.read(&path).expect("read"), "a\nstaged\n");
+        config.flags.remove(Flags::STAGED);
+        config.changed_since = Some("HEAD".to_owned());
+        let changes = Changes::new(&config, dir.path())
+            .expect("Changes::new")
+            .expect("changed_since");
+        assert_eq!(changes.read(&path).expect("read"), "work\nstaged\nwork\n");
+    }
+}
@@ -6,21 +6,35 @@
 //  @author hanepjiv <hanepjiv@gmail.com>
 //  @copyright The MIT License (MIT) / Apache License Version 2.0
 //  @since 2016/10/13
-//  @date 2025/07/12
+//  @date 2026/10/18
 
 // ////////////////////////////////////////////////////////////////////////////
 // use  =======================================================================
 use alloc::collections::{BTreeMap, btree_map::Entry};
-use std::ffi::OsString;
+use std::{
+    ffi::OsString,
+    path::{Component, Path, PathBuf},
+};
 // ----------------------------------------------------------------------------
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use log::info;
 use serde::Deserialize;
 // ----------------------------------------------------------------------------
 use crate::{
+    backup::Backup,
     error::Error,
     flags::Flags,
     language::{Language, LanguageSrc},
+    modeline::Modeline,
+    report::Format,
+    width::WidthMode,
 };
 // ////////////////////////////////////////////////////////////////////////////
+// define  ====================================================================
+const CONFIG_PROJECT_PATH: &str = ".column79.toml";
+const CARGO_MANIFEST_PATH: &str = "Cargo.toml";
+const CARGO_METADATA_KEY: &str = "column79";
+// ////////////////////////////////////////////////////////////////////////////
 // ============================================================================
 /// struct `ConfigSrc`
 #[derive(Debug, Deserialize)]
@@ -31,10 +45,30 @@ pub(crate) struct ConfigSrc {
     pub separator_threshold: Option<usize>,
     /// ask
     pub ask: Option<bool>,
+    /// format
+    pub format: Option<String>,
+    /// `width_mode`
+    pub width_mode: Option<String>,
+    /// `tab_width`
+    pub tab_width: Option<usize>,
+    /// jobs
+    pub jobs: Option<usize>,
+    /// backup
+    pub backup: Option<String>,
+    /// `backup_dir`
+    pub backup_dir: Option<PathBuf>,
+    /// `backup_suffix`
+    pub backup_suffix: Option<String>,
     /// language
     pub language: Option<String>,
+    /// include
+    pub include: Option<Vec<String>>,
+    /// exclude
+    pub exclude: Option<Vec<String>>,
     /// languages
     pub languages: Option<Vec<LanguageSrc>>,
+    /// overrides
+    pub overrides: Option<Vec<Override>>,
 }
 // ////////////////////////////////////////////////////////////////////////////
 // ============================================================================
@@ -45,12 +79,55 @@ pub(crate) struct Config {
     pub column: usize,
     /// `separator_threshold`
     pub separator_threshold: usize,
+    /// `force_column`, over the column of every language and file.
+    pub force_column: Option<usize>,
+    /// `force_separator_threshold`
+    pub force_separator_threshold: Option<usize>,
     /// flags
     pub flags: Flags,
+    /// format
+    pub format: Format,
+    /// `width_mode`
+    pub width_mode: WidthMode,
+    /// `tab_width`
+    pub tab_width: usize,
+    /// jobs, 0 for the available parallelism.
+    pub jobs: usize,
+    /// backup
+    pub backup: Backup,
+    /// `backup_dir`, relative to `root`.
+    pub backup_dir: PathBuf,
+    /// `backup_suffix`
+    pub backup_suffix: String,
     /// language
     pub language: String,
+    /// include
+    pub include: Vec<String>,
+    /// exclude
+    pub exclude: Vec<String>,
+    /// baseline
+    pub baseline: Option<PathBuf>,
+    /// `write_baseline`
+    pub write_baseline: Option<PathBuf>,
+    /// `changed_since`
+    pub changed_since: Option<String>,
+    /// root, the directory of the project config, which `include`,
+    /// `exclude` and `overrides` are relative to.
+    pub root: PathBuf,
+    /// current, the directory relative paths are resolved from.
+    pub current: PathBuf,
+    /// `language_srcs`, layered by name in the order of definition.
+    language_srcs: Vec<LanguageSrc>,
     /// languages
     pub languages: BTreeMap<String, Language>,
+    /// overrides, the later winning.
+    pub overrides: Vec<Override>,
+    /// `override_sets`, the glob sets of `overrides`.
+    override_sets: Vec<GlobSet>,
+    /// `include_set`
+    include_set: GlobSet,
+    /// `exclude_set`
+    exclude_set: GlobSet,
 }
 // ============================================================================
 impl Default for Config {
@@ -58,25 +135,167 @@ impl Default for Config {
         Self {
             column: 79,
             separator_threshold: 12,
+            force_column: None,
+            force_separator_threshold: None,
             flags: Flags::empty(),
+            format: Format::Text,
+            width_mode: WidthMode::Graphemes,
+            tab_width: 8,
+            jobs: 1,
+            backup: Backup::Sibling,
+            backup_dir: PathBuf::from(".column79.backup"),
+            backup_suffix: String::from(".backup"),
             language: String::from("cargo"),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            baseline: None,
+            write_baseline: None,
+            changed_since: None,
+            root: PathBuf::new(),
+            current: PathBuf::new(),
+            language_srcs: Vec::new(),
             languages: BTreeMap::new(),
+            overrides: Vec::new(),
+            override_sets: Vec::new(),
+            include_set: GlobSet::empty(),
+            exclude_set: GlobSet::empty(),
         }
     }
 }
 // ============================================================================
+/// struct Override
+///
+/// An `[[overrides]]` entry for the files matching `globs`, relative to the
+/// project root like `include` and `exclude`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(crate) struct Override {
+    /// globs
+    #[serde(default)]
+    pub globs: Vec<String>,
+    /// column
+    pub column: Option<usize>,
+    /// `separator_threshold`
+    pub separator_threshold: Option<usize>,
+    /// language, instead of the detected one.
+    pub language: Option<String>,
+    /// skip, not to check the files at all.
+    pub skip: Option<bool>,
+}
+// ============================================================================
+impl Override {
+    // ========================================================================
+    /// layer
+    fn layer(&mut self, src: &Self) {
+        self.column = src.column.or(self.column);
+        self.separator_threshold =
+            src.separator_threshold.or(self.separator_threshold);
+        if src.language.is_some() {
+            self.language.clone_from(&src.language);
+        }
+        self.skip = src.skip.or(self.skip);
+    }
+    // ------------------------------------------------------------------------
+    /// `is_skip`
+    pub(crate) fn is_skip(&self) -> bool {
+        self.skip.unwrap_or(false)
+    }
+}
+// ============================================================================
+/// struct Limits
+///
+/// The limits a file is checked against, resolved per file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Limits {
+    /// column
+    pub column: usize,
+    /// `separator_threshold`
+    pub separator_threshold: usize,
+}
+// ============================================================================
 impl Config {
     // ========================================================================
     /// new
-    pub(crate) fn new(path: &OsString) -> Result<Self, Error> {
+    ///
+    /// The config of the built-in `defaults`, which the config files are
+    /// imported over.
+    pub(crate) fn new(defaults: &str) -> Result<Self, Error> {
         let mut config = Self::default();
-        config.import(path)?;
+        config.import_src(toml::from_str(defaults)?)?;
         Ok(config)
     }
     // ========================================================================
     /// import
     pub(crate) fn import(&mut self, path: &OsString) -> Result<(), Error> {
         let src: ConfigSrc = toml::from_str(&std::fs::read_to_string(path)?)?;
+        self.import_src(src)
+    }
+    // ------------------------------------------------------------------------
+    /// `import_project`
+    ///
+    /// Searches upward from `path` for a `.column79.toml`, or a `Cargo.toml`
+    /// with a `[package.metadata.column79]` or
+    /// `[workspace.metadata.column79]` table, and imports the nearest one;
+    /// its directory becomes `root`.
+    pub(crate) fn import_project(
+        &mut self,
+        path: &Path,
+    ) -> Result<Option<PathBuf>, Error> {
+        let start = path.canonicalize()?;
+        let mut dir = if start.is_dir() {
+            Some(start.as_path())
+        } else {
+            start.parent()
+        };
+        while let Some(d) = dir {
+            let project = d.join(CONFIG_PROJECT_PATH);
+            if project.is_file() {
+                let src: ConfigSrc =
+                    toml::from_str(&std::fs::read_to_string(&project)?)
+                        .map_err(|e| {
+                            Error::ParseConfig(
+                                project.display().to_string(),
+                                e,
+                            )
+                        })?;
+                self.import_src(src)?;
+                self.root = d.to_path_buf();
+                return Ok(Some(project));
+            }
+            let manifest = d.join(CARGO_MANIFEST_PATH);
+            if manifest.is_file() {
+                if let Some(src) = Self::cargo_metadata(&manifest)? {
+                    self.import_src(src)?;
+                    self.root = d.to_path_buf();
+                    return Ok(Some(manifest));
+                }
+            }
+            dir = d.parent();
+        }
+        Ok(None)
+    }
+    // ------------------------------------------------------------------------
+    /// `cargo_metadata`
+    fn cargo_metadata(path: &Path) -> Result<Option<ConfigSrc>, Error> {
+        let map_err = |e: toml::de::Error| {
+            Error::ParseConfig(path.display().to_string(), e)
+        };
+        let manifest: toml::Table =
+            toml::from_str(&std::fs::read_to_string(path)?)
+                .map_err(map_err)?;
+        for i in ["package", "workspace"] {
+            if let Some(x) = manifest
+                .get(i)
+                .and_then(|x| x.get("metadata"))
+                .and_then(|x| x.get(CARGO_METADATA_KEY))
+            {
+                return Ok(Some(x.clone().try_into().map_err(map_err)?));
+            }
+        }
+        Ok(None)
+    }
+    // ------------------------------------------------------------------------
+    /// `import_src`
+    fn import_src(&mut self, src: ConfigSrc) -> Result<(), Error> {
         if let Some(x) = src.column {
             self.column = x;
         }
@@ -89,29 +308,106 @@ impl Config {
             } else {
                 self.flags.insert(Flags::NOASK);
             }
-        } else {
-            self.flags.remove(Flags::NOASK);
+        }
+        if let Some(x) = src.format {
+            self.format = Format::from(x.as_str());
+        }
+        if let Some(x) = src.width_mode {
+            self.width_mode = WidthMode::from(x.as_str());
+        }
+        if let Some(x) = src.tab_width {
+            self.tab_width = x;
+        }
+        if let Some(x) = src.jobs {
+            self.jobs = x;
+        }
+        if let Some(x) = src.backup {
+            self.backup = Backup::from(x.as_str());
+        }
+        if let Some(x) = src.backup_dir {
+            self.backup_dir = x;
+        }
+        if let Some(x) = src.backup_suffix {
+            self.backup_suffix = x;
         }
         if let Some(x) = src.language {
             self.language = x;
         }
+        if let Some(x) = src.include {
+            self.include = x;
+        }
+        if let Some(x) = src.exclude {
+            self.exclude = x;
+        }
         if let Some(xs) = src.languages {
             for x in xs {
-                let l = Language::from_src(x, &self.languages)?;
-                if self.languages.insert(l.peek_name().clone(), l).is_some() {
-                    return Err(Error::InvalidConfig(
-                        "::column79::language::Config::import(...): \
-                         languages base: insert failed"
-                            .to_owned(),
-                    ));
+                match self.language_srcs.iter_mut().find(|i| i.name == x.name)
+                {
+                    Some(i) => i.layer(x),
+                    None => self.language_srcs.push(x),
                 }
             }
+            let mut languages = BTreeMap::new();
+            for i in &self.language_srcs {
+                let l = Language::from_src(i.clone(), &languages)?;
+                drop(languages.insert(l.peek_name().clone(), l));
+            }
+            self.languages = languages;
+        }
+        if let Some(x) = src.overrides {
+            self.overrides.extend(x);
         }
         Ok(())
     }
     // ========================================================================
     /// validation
     pub(crate) fn validation(&mut self) -> Result<(), Error> {
+        if Format::Unknown == self.format {
+            return Err(Error::InvalidConfig(
+                "::column79::config::Config::validation(&self): \
+                 invalid format"
+                    .to_owned(),
+            ));
+        }
+        if WidthMode::Unknown == self.width_mode {
+            return Err(Error::InvalidConfig(
+                "::column79::config::Config::validation(&self): \
+                 invalid width_mode"
+                    .to_owned(),
+            ));
+        }
+        if Backup::Unknown == self.backup {
+            return Err(Error::InvalidConfig(
+                "::column79::config::Config::validation(&self): \
+                 invalid backup"
+                    .to_owned(),
+            ));
+        }
+        if Backup::Sibling == self.backup && self.backup_suffix.is_empty() {
+            return Err(Error::InvalidConfig(
+                "::column79::config::Config::validation(&self): \
+                 empty backup_suffix"
+                    .to_owned(),
+            ));
+        }
+        self.include_set = Self::glob_set(&self.include)?;
+        self.exclude_set = Self::glob_set(&self.exclude)?;
+        self.override_sets = self
+            .overrides
+            .iter()
+            .map(|x| Self::glob_set(&x.globs))
+            .collect::<Result<_, _>>()?;
+        if let Some(x) = self
+            .overrides
+            .iter()
+            .filter_map(|x| x.language.as_ref())
+            .find(|x| !self.languages.contains_key(*x))
+        {
+            return Err(Error::InvalidConfig(format!(
+                "::column79::config::Config::validation(&self): \
+                 overrides language not found {x}"
+            )));
+        }
         match self.languages.entry(self.language.clone()) {
             Entry::<'_, _, _, _>::Vacant(_) => {
                 Err(Error::InvalidConfig(format!(
@@ -123,14 +419,296 @@ impl Config {
             Entry::<'_, _, _, _>::Occupied(_) => Ok(()),
         }
     }
+    // ------------------------------------------------------------------------
+    /// `glob_set`
+    fn glob_set(patterns: &[String]) -> Result<GlobSet, Error> {
+        let mut builder = GlobSetBuilder::new();
+        for i in patterns {
+            let _ = builder.add(Glob::new(i)?);
+        }
+        Ok(builder.build()?)
+    }
+    // ========================================================================
+    /// jobs
+    pub(crate) fn jobs(&self) -> usize {
+        if 0 == self.jobs {
+            std::thread::available_parallelism().map_or(1, usize::from)
+        } else {
+            self.jobs
+        }
+    }
+    // ========================================================================
+    /// width
+    pub(crate) fn width(&self, lang: &Language, s: &str) -> usize {
+        self.width_mode
+            .width(lang.peek_tab_width().unwrap_or(self.tab_width), s)
+    }
+    // ========================================================================
+    /// `peek_exclude_set`
+    pub(crate) const fn peek_exclude_set(&self) -> &GlobSet {
+        &self.exclude_set
+    }
+    // ------------------------------------------------------------------------
+    /// relative
+    pub(crate) fn relative(&self, path: &Path) -> PathBuf {
+        Self::relative_to(&self.root, &self.current, path)
+    }
+    // ------------------------------------------------------------------------
+    /// `relative_to`
+    ///
+    /// `path` resolved from `current`, normalized lexically and made
+    /// relative to `root`; the whole path when outside of `root`.
+    pub(crate) fn relative_to(
+        root: &Path,
+        current: &Path,
+        path: &Path,
+    ) -> PathBuf {
+        let mut ret = PathBuf::new();
+        for i in current.join(path).components() {
+            match i {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    let _ = ret.pop();
+                }
+                x => ret.push(x),
+            }
+        }
+        match ret.strip_prefix(root) {
+            Ok(x) => x.to_path_buf(),
+            Err(_) => ret,
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// `is_included`
+    pub(crate) fn is_included(&self, path: &Path) -> bool {
+        (self.include.is_empty() || self.include_set.is_match(path))
+            && !self.exclude_set.is_match(path)
+    }
     // ========================================================================
     /// `check_path`
-    pub(crate) fn check_path(
-        &self,
-        path: &std::path::PathBuf,
-    ) -> Option<&Language> {
+    pub(crate) fn check_path(&self, path: &Path) -> Option<&Language> {
         self.languages
             .get(&self.language)?
             .check_path(path, &self.languages)
     }
+    // ------------------------------------------------------------------------
+    /// `check_text`
+    pub(crate) fn check_text(
+        &self,
+        path: &Path,
+        text: &str,
+    ) -> Option<&Language> {
+        self.languages.get(&self.language)?.check_text(
+            path,
+            text,
+            &self.languages,
+        )
+    }
+    // ------------------------------------------------------------------------
+    /// `check_override`
+    ///
+    /// The `overrides` matching `path` layered in order.
+    pub(crate) fn check_override(&self, path: &Path) -> Override {
+        let mut ret = Override::default();
+        for (x, set) in self.overrides.iter().zip(&self.override_sets) {
+            if set.is_match(path) {
+                ret.layer(x);
+            }
+        }
+        ret
+    }
+    // ------------------------------------------------------------------------
+    /// `check_mode`
+    ///
+    /// The language named `mode` by its name or one of its `modes`.
+    pub(crate) fn check_mode(&self, mode: &str) -> Option<&Language> {
+        self.languages
+            .get(mode)
+            .or_else(|| self.languages.values().find(|x| x.check_mode(mode)))
+    }
+    // ------------------------------------------------------------------------
+    /// resolve
+    ///
+    /// The language and the limits of a file of `lang` containing `text`.
+    /// The forced limits override `over`, which overrides the modeline,
+    /// which overrides the language, whose limits override the global ones.
+    pub(crate) fn resolve<'a>(
+        &'a self,
+        lang: &'a Language,
+        over: &Override,
+        text: &str,
+    ) -> (&'a Language, Limits) {
+        let modeline = Modeline::parse(text);
+        let lang = match modeline.mode {
+            Some(ref x) if over.language.is_none() => {
+                self.check_mode(x).unwrap_or_else(|| {
+                    info!("Config::resolve: unknown mode {x:?}");
+                    lang
+                })
+            }
+            Some(_) | None => lang,
+        };
+        let limits = Limits {
+            column: self
+                .force_column
+                .or(over.column)
+                .or(modeline.column)
+                .or(lang.peek_column())
+                .unwrap_or(self.column),
+            separator_threshold: self
+                .force_separator_threshold
+                .or(over.separator_threshold)
+                .or(lang.peek_separator_threshold())
+                .unwrap_or(self.separator_threshold),
+        };
+        (lang, limits)
+    }
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    // use  ===================================================================
+    use std::path::Path;
+    // ------------------------------------------------------------------------
+    use super::{Config, ConfigSrc};
+    // ========================================================================
+    fn config(src: &str) -> Config {
+        let src: ConfigSrc = toml::from_str(src).expect("toml");
+        let mut ret = Config::default();
+        ret.import_src(src).expect("import_src");
+        ret.validation().expect("validation");
+        ret
+    }
+    // ========================================================================
+    #[test]
+    fn import_project() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path().canonicalize().expect("canonicalize");
+        for i in ["a/b/c", "both", "crate/src", "workspace/x"] {
+            std::fs::create_dir_all(root.join(i)).expect("create_dir_all");
+        }
+        for (name, text) in [
+            ("Cargo.toml", "[package.metadata.column79]\ncolumn = 100\n"),
+            ("a/.column79.toml", "column = 90\n"),
+            ("a/b/Cargo.toml", "[package]\nname = \"b\"\n"),
+            ("both/.column79.toml", "column = 80\n"),
+            (
+                "both/Cargo.toml",
+                "[package.metadata.column79]\ncolumn = 1\n",
+            ),
+            ("crate/Cargo.toml", "[package]\nname = \"crate\"\n"),
+            (
+                "workspace/Cargo.toml",
+                "[workspace.metadata.column79]\ncolumn = 70\n",
+            ),
+            ("a/b/c/x.rs", ""),
+        ] {
+            std::fs::write(root.join(name), text).expect("write");
+        }
+        for (path, found, column) in [
+            ("a/b/c/x.rs", "a/.column79.toml", 90),
+            ("a/b", "a/.column79.toml", 90),
+            ("both", "both/.column79.toml", 80),
+            ("crate/src", "Cargo.toml", 100),
+            ("workspace/x", "workspace/Cargo.toml", 70),
+        ] {
+            let mut config = Config::default();
+            assert_eq!(
+                config
+                    .import_project(&root.join(path))
+                    .expect("import_project"),
+                Some(root.join(found)),
+                "{path}"
+            );
+            assert_eq!(config.column, column, "{path}");
+            assert_eq!(
+                Some(config.root.as_path()),
+                root.join(found).parent(),
+                "{path}"
+            );
+        }
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn relative_to() {
+        let root = Path::new("/p");
+        for (current, path, expected) in [
+            ("/p", "./src/gen/g.rs", "src/gen/g.rs"),
+            ("/p/src", "gen/g.rs", "src/gen/g.rs"),
+            ("/p/src", "../tests/t.rs", "tests/t.rs"),
+            ("/q", "/p/src/lib.rs", "src/lib.rs"),
+            ("/q", "x.rs", "/q/x.rs"),
+        ] {
+            assert_eq!(
+                Config::relative_to(root, Path::new(current), Path::new(path)),
+                Path::new(expected)
+            );
+        }
+        let mut config = Config {
+            exclude: vec!["**/generated/**".to_owned()],
+            root: root.to_path_buf(),
+            current: root.join("src"),
+            ..Config::default()
+        };
+        config.exclude_set =
+            Config::glob_set(&config.exclude).expect("glob_set");
+        assert!(
+            !config.is_included(&config.relative(Path::new("generated/a.rs")))
+        );
+        assert!(config.is_included(&config.relative(Path::new("lib.rs"))));
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn overrides() {
+        let config = config(
+            r#"
+            language = "c"
+            [[languages]]
+            name = "c"
+            [[languages]]
+            name = "sh"
+            [[overrides]]
+            globs = ["vendor/**"]
+            column = 120
+            separator_threshold = 4
+            [[overrides]]
+            globs = ["vendor/gen/**", "*.gen.c"]
+            skip = true
+            [[overrides]]
+            globs = ["vendor/gen/keep.c"]
+            skip = false
+            language = "sh"
+            "#,
+        );
+        let over = config.check_override(Path::new("vendor/a.c"));
+        assert_eq!(over.column, Some(120));
+        assert_eq!(over.separator_threshold, Some(4));
+        assert!(!over.is_skip());
+        assert!(config.check_override(Path::new("vendor/gen/a.c")).is_skip());
+        assert!(config.check_override(Path::new("src/a.gen.c")).is_skip());
+        let over = config.check_override(Path::new("vendor/gen/keep.c"));
+        assert!(!over.is_skip());
+        assert_eq!(over.column, Some(120));
+        assert_eq!(over.language.as_deref(), Some("sh"));
+        assert_eq!(config.check_override(Path::new("a.c")).column, None);
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn overrides_language() {
+        let src: ConfigSrc = toml::from_str(
+            r#"
+            language = "c"
+            [[languages]]
+            name = "c"
+            [[overrides]]
+            globs = ["*.h"]
+            language = "c++"
+            "#,
+        )
+        .expect("toml");
+        let mut config = Config::default();
+        config.import_src(src).expect("import_src");
+        assert!(config.validation().is_err());
+    }
 }
@@ -6,7 +6,7 @@
 //  @author hanepjiv <hanepjiv@gmail.com>
 //  @copyright The MIT License (MIT) / Apache License Version 2.0
 //  @since 2016/10/13
-//  @date 2026/05/30
+//  @date 2026/10/18
 
 // ////////////////////////////////////////////////////////////////////////////
 // use  =======================================================================
@@ -21,12 +21,20 @@ pub enum Error {
     EnvVar(std::env::VarError),
     /// IO.
     IO(std::io::Error),
+    /// Ignore.
+    Ignore(ignore::Error),
+    /// Glob.
+    Glob(globset::Error),
     /// `TOMLSer`.
     TOMLSer(toml::ser::Error),
     /// `TOMLDe`.
     TOMLDe(toml::de::Error),
     /// `ParseConfig`.
     ParseConfig(String, toml::de::Error),
+    /// JSON.
+    JSON(serde_json::Error),
+    /// Git.
+    Git(String),
     /// Column79.
     Column79(String),
     /// `InvalidConfig`.
@@ -49,6 +57,20 @@ impl From<std::io::Error> for Error {
     }
 }
 // ----------------------------------------------------------------------------
+impl From<ignore::Error> for Error {
+    #[inline]
+    fn from(e: ignore::Error) -> Self {
+        Self::Ignore(e)
+    }
+}
+// ----------------------------------------------------------------------------
+impl From<globset::Error> for Error {
+    #[inline]
+    fn from(e: globset::Error) -> Self {
+        Self::Glob(e)
+    }
+}
+// ----------------------------------------------------------------------------
 impl From<toml::ser::Error> for Error {
     #[inline]
     fn from(e: toml::ser::Error) -> Self {
@@ -62,6 +84,13 @@ impl From<toml::de::Error> for Error {
         Self::TOMLDe(e)
     }
 }
+// ----------------------------------------------------------------------------
+impl From<serde_json::Error> for Error {
+    #[inline]
+    fn from(e: serde_json::Error) -> Self {
+        Self::JSON(e)
+    }
+}
 // ============================================================================
 impl core::fmt::Display for Error {
     #[inline]
@@ -77,13 +106,17 @@ impl CoreError for Error {
         match *self {
             Self::EnvVar(ref e) => Some(e),
             Self::IO(ref e) => Some(e),
+            Self::Ignore(ref e) => Some(e),
+            Self::Glob(ref e) => Some(e),
             Self::TOMLSer(ref e) => Some(e),
+            Self::JSON(ref e) => Some(e),
 
             Self::TOMLDe(ref e) | Self::ParseConfig(_, ref e) => Some(e),
 
-            Self::Column79(_) | Self::InvalidConfig(_) | Self::Inspect(_) => {
-                None
-            }
+            Self::Git(_)
+            | Self::Column79(_)
+            | Self::InvalidConfig(_)
+            | Self::Inspect(_) => None,
         }
     }
 }
@@ -6,7 +6,7 @@
 //  @author hanepjiv <hanepjiv@gmail.com>
 //  @copyright The MIT License (MIT) / Apache License Version 2.0
 //  @since 2016/10/15
-//  @date 2025/04/06
+//  @date 2026/10/18
 
 // ////////////////////////////////////////////////////////////////////////////
 // use  =======================================================================
@@ -19,5 +19,13 @@ bitflags! {
     pub struct Flags: u32 {
     /// const NOASK
     const NOASK = 0b0000_0001_u32;
+    /// const NOIGNORE
+    const NOIGNORE = 0b0000_0010_u32;
+    /// const DRYRUN
+    const DRYRUN = 0b0000_0100_u32;
+    /// const KEEPMTIME
+    const KEEPMTIME = 0b0000_1000_u32;
+    /// const STAGED
+    const STAGED = 0b0001_0000_u32;
     }
 }
@@ -6,50 +6,113 @@
 //  @author hanepjiv <hanepjiv@gmail.com>
 //  @copyright The MIT License (MIT) / Apache License Version 2.0
 //  @since 2016/10/14
-//  @date 2026/05/30
+//  @date 2026/10/18
 
 // ////////////////////////////////////////////////////////////////////////////
 // use  =======================================================================
-use core::cmp::Ordering::{Equal, Greater, Less};
+use alloc::collections::BTreeMap;
+use core::{
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+#[cfg(unix)]
+use std::os::unix::fs::{MetadataExt as _, fchown};
 use std::{
-    fs::File,
-    io::{
-        BufRead as _, BufReader, BufWriter, Seek as _, SeekFrom, Write as _,
-    },
-    path::Path,
+    fs::{File, Metadata, OpenOptions},
+    io::Write as _,
+    path::{Component, Path, PathBuf},
+    sync::{Mutex, PoisonError, mpsc},
+    thread,
 };
 // ----------------------------------------------------------------------------
+use log::{info, warn};
 use regex::Regex;
-use tempfile::tempfile;
-use unicode_segmentation::UnicodeSegmentation as _;
+use similar::TextDiff;
 // ----------------------------------------------------------------------------
 use crate::{
-    config::Config, error::Error, flags::Flags, language::Language,
-    line_type::LineType,
+    backup::Backup,
+    baseline::Baseline,
+    changes::Changes,
+    config::{Config, Limits, Override},
+    error::Error,
+    flags::Flags,
+    language::Language,
+    line_type::{LineClassifier, LineType},
+    modeline::Modeline,
+    reflow,
+    report::{Reporter, Summary, Violation},
 };
 // ////////////////////////////////////////////////////////////////////////////
+// define  ====================================================================
+const BOM: &str = "\u{feff}";
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+/// `split_eol`.
+fn split_eol(line: &str) -> (&str, &str) {
+    line.strip_suffix("\r\n")
+        .map(|x| (x, "\r\n"))
+        .or_else(|| line.strip_suffix('\n').map(|x| (x, "\n")))
+        .unwrap_or((line, ""))
+}
+// ----------------------------------------------------------------------------
+/// `display_name`.
+///
+/// `path` relative to the current directory, without `.` components.
+fn display_name(path: &Path) -> Result<PathBuf, Error> {
+    let current = std::env::current_dir()?;
+    Ok(path
+        .strip_prefix(&current)
+        .unwrap_or(path)
+        .components()
+        .filter(|x| Component::CurDir != *x)
+        .collect())
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+/// struct Target.
+///
+/// A file to inspect, with its language and the overrides matching it.
+#[derive(Debug, Clone)]
+pub(crate) struct Target<'a> {
+    /// language.
+    pub language: &'a Language,
+    /// over.
+    pub over: Override,
+    /// path.
+    pub path: PathBuf,
+}
+// ////////////////////////////////////////////////////////////////////////////
 // ============================================================================
 /// trait Inspector.
 pub(crate) trait Inspector: core::fmt::Debug {
     // ========================================================================
     /// inspect.
-    fn inspect(&self, lang: &Language, path: &Path) -> Result<(), Error>;
+    fn inspect(&self, target: &Target<'_>) -> Result<(), Error>;
     // ========================================================================
     /// `inspect_impl`.
+    ///
+    /// Classifies the lines of `text`.  A UTF-8 BOM is not part of the first
+    /// line, and `func` receives the line ending of each line: `"\r\n"`,
+    /// `"\n"`, or `""` for a last line without one.
     #[expect(clippy::arithmetic_side_effects, reason = "checked")]
-    fn inspect_impl(
+    fn inspect_impl<F>(
         &self,
-        conf: &Config,
+        limits: Limits,
         lang: &Language,
-        path: &Path,
-        func: &mut impl FnMut(usize, &LineType, &str) -> Result<(), Error>,
-    ) -> Result<(), Error> {
-        let file_in = File::open(path)?;
-        let fin = BufReader::new(&file_in);
-        for (row, line) in fin.lines().enumerate() {
-            let l = &line?;
-            let line_type = LineType::new(conf, lang, l);
-            func(row + 1, &line_type, l)?;
+        text: &str,
+        func: &mut F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(usize, &LineType, &str, &str) -> Result<(), Error>,
+    {
+        let mut classifier = LineClassifier::new(limits, lang);
+        let body = text.strip_prefix(BOM).unwrap_or(text);
+        let mut offset = 0;
+        for (row, line) in body.split_inclusive('\n').enumerate() {
+            let (l, eol) = split_eol(line);
+            let line_type = classifier.classify(l, &body[offset..]);
+            func(row + 1, &line_type, l, eol)?;
+            offset += line.len();
         }
         Ok(())
     }
@@ -59,26 +122,29 @@ pub(crate) trait Inspector: core::fmt::Debug {
         &self,
         path: &Path,
         row: usize,
+        width: usize,
         line: &str,
     ) -> Result<(), Error> {
         println!(
-            "{0}({row}): {1} : {line}",
+            "{0}({row}): {width} : {line}",
             path.as_os_str().to_str().ok_or_else(|| Error::Inspect(
                 "Inspector::println_line: path".to_owned()
             ))?,
-            line.graphemes(true).count(),
         );
         Ok(())
     }
     // ========================================================================
     /// ask.
+    ///
+    /// Takes `default` without asking under `Flags::NOASK` or
+    /// `Flags::DRYRUN`, so that a dry run only prints the diff.
     fn ask(
         &self,
         config: &Config,
         msg: &str,
         default: bool,
     ) -> Result<bool, Error> {
-        if config.flags.contains(Flags::NOASK) {
+        if config.flags.intersects(Flags::NOASK | Flags::DRYRUN) {
             return Ok(default);
         }
         crate::ask::ask(msg, default)
@@ -90,20 +156,21 @@ pub(crate) trait Inspector: core::fmt::Debug {
         lang: &Language,
         column: usize,
         line_type: &LineType,
-        line: &str,
+        width: usize,
     ) -> bool {
         match *line_type {
             LineType::LineComment(_, _)
             | LineType::LineSeparator(_, _)
-            | LineType::Other => column >= line.graphemes(true).count(),
+            | LineType::BlockInner(_, _, _)
+            | LineType::Other => column >= width,
+
+            LineType::Ignored => true,
 
             LineType::BlockComment(_, _, _) => {
-                column >= line.graphemes(true).count()
-                    && !lang.has_line_comment()
+                column >= width && !lang.has_line_comment()
             }
             LineType::BlockSeparator(_, _, _) => {
-                column == line.graphemes(true).count()
-                    && !lang.has_line_comment()
+                column == width && !lang.has_line_comment()
             }
         }
     }
@@ -111,36 +178,221 @@ pub(crate) trait Inspector: core::fmt::Debug {
 // ////////////////////////////////////////////////////////////////////////////
 // ============================================================================
 /// struct Checker.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub(crate) struct Checker<'a> {
     /// config.
     config: &'a Config,
+    /// reporter.
+    reporter: Reporter,
+    /// baseline.
+    baseline: Mutex<Baseline>,
+    /// changes.
+    changes: Option<Changes>,
 }
 // ============================================================================
 impl<'a> Checker<'a> {
     // ========================================================================
     /// new.
-    pub(crate) const fn new(config: &'a Config) -> Self {
-        Checker { config }
+    pub(crate) fn new(
+        config: &'a Config,
+        changes: Option<Changes>,
+    ) -> Result<Self, Error> {
+        let baseline = match config.baseline {
+            Some(ref x) if config.write_baseline.is_none() => {
+                Baseline::load(x)?
+            }
+            Some(_) | None => Baseline::new(),
+        };
+        Ok(Checker {
+            config,
+            reporter: Reporter::new(config.format),
+            baseline: Mutex::new(baseline),
+            changes,
+        })
+    }
+    // ========================================================================
+    /// finish.
+    pub(crate) fn finish(&self) -> Result<Summary, Error> {
+        if let Some(ref x) = self.config.write_baseline {
+            self.baseline
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .save(x)?;
+            info!("Checker::finish: write baseline {}", x.display());
+        }
+        self.reporter.finish()
+    }
+    // ========================================================================
+    /// `inspect_all`.
+    ///
+    /// Inspects `files` on `jobs` threads and reports them in order.
+    pub(crate) fn inspect_all(
+        &self,
+        files: &[Target<'_>],
+        jobs: usize,
+    ) -> Result<(), Error> {
+        if jobs < 2 {
+            for i in files {
+                self.inspect(i)?;
+            }
+            return Ok(());
+        }
+        let next = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            let (tx, rx) = mpsc::channel();
+            for _ in 0..jobs.min(files.len()) {
+                let tx = tx.clone();
+                let next = &next;
+                drop(scope.spawn(move || {
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(target) = files.get(i) else {
+                            break;
+                        };
+                        if tx.send((i, self.collect(target))).is_err() {
+                            break;
+                        }
+                    }
+                }));
+            }
+            drop(tx);
+            let mut pending = BTreeMap::new();
+            let mut done = 0_usize;
+            for (i, x) in rx {
+                drop(pending.insert(i, x));
+                while let Some(x) = pending.remove(&done) {
+                    if let Some(target) = files.get(done) {
+                        self.emit(&target.path, x?)?;
+                    }
+                    done = done.saturating_add(1);
+                }
+            }
+            Ok(())
+        })
+    }
+    // ========================================================================
+    /// collect.
+    ///
+    /// The violations of `target`, or `None` if it is not checked.
+    fn collect(
+        &self,
+        target: &Target<'_>,
+    ) -> Result<Option<Vec<Violation>>, Error> {
+        let rows = match self.changes {
+            Some(ref x) => match x.rows(&target.path) {
+                Some(rows) => Some(rows),
+                None => return Ok(None),
+            },
+            None => None,
+        };
+        let text = match self.changes {
+            Some(ref x) => x.read(&target.path)?,
+            None => std::fs::read_to_string(&target.path)?,
+        };
+        self.violations(target, &text, rows).map(Some)
+    }
+    // ------------------------------------------------------------------------
+    /// violations.
+    ///
+    /// The violations of `text` read from `target`, in `rows` if given.
+    fn violations(
+        &self,
+        target: &Target<'_>,
+        text: &str,
+        rows: Option<&[Range<usize>]>,
+    ) -> Result<Vec<Violation>, Error> {
+        let (lang, limits) =
+            self.config.resolve(target.language, &target.over, text);
+        let c = limits.column;
+        let p = target.path.as_os_str().to_str().ok_or_else(|| {
+            Error::Inspect("Checker::inspect: path".to_owned())
+        })?;
+        let mut ret = Vec::new();
+        self.inspect_impl(limits, lang, text, &mut |row, line_type, l, _| {
+            let width = self.config.width(lang, l);
+            if rows.is_none_or(|x| x.iter().any(|r| r.contains(&row)))
+                && !self.check_type(lang, c, line_type, width)
+            {
+                ret.push(Violation {
+                    path: p.to_owned(),
+                    row,
+                    width,
+                    column: c,
+                    line_type: line_type.name(),
+                    language: lang.peek_name().clone(),
+                    line: String::from(l),
+                });
+            }
+            Ok(())
+        })?;
+        Ok(ret)
+    }
+    // ------------------------------------------------------------------------
+    /// emit.
+    ///
+    /// Reports the violations of `path` not recorded to, or known by, the
+    /// baseline, which keys `path` relative to the project root.
+    fn emit(
+        &self,
+        path: &Path,
+        violations: Option<Vec<Violation>>,
+    ) -> Result<(), Error> {
+        let Some(violations) = violations else {
+            return Ok(());
+        };
+        let key = self.config.relative(path);
+        let key = key.to_string_lossy();
+        self.reporter.file();
+        let mut baseline =
+            self.baseline.lock().unwrap_or_else(PoisonError::into_inner);
+        for v in violations {
+            if self.config.write_baseline.is_some() {
+                baseline.insert(&key, &v.line);
+            } else if !baseline.take(&key, &v.line) {
+                self.reporter.violation(v)?;
+            }
+        }
+        Ok(())
+    }
+    // ========================================================================
+    /// `inspect_text`.
+    ///
+    /// Inspects `text` as if read from `target`, such as the standard input.
+    pub(crate) fn inspect_text(
+        &self,
+        target: &Target<'_>,
+        text: &str,
+    ) -> Result<(), Error> {
+        self.emit(&target.path, Some(self.violations(target, text, None)?))
     }
 }
 // ============================================================================
 impl Inspector for Checker<'_> {
     // ========================================================================
     /// inspect.
-    fn inspect(&self, lang: &Language, path: &Path) -> Result<(), Error> {
-        let c = self.config.column;
-        self.inspect_impl(self.config, lang, path, &mut |row, line_type, l| {
-            if self.check_type(lang, c, line_type, l) {
-                Ok(())
-            } else {
-                self.println_line(path, row, l)
-            }
-        })
+    fn inspect(&self, target: &Target<'_>) -> Result<(), Error> {
+        self.emit(&target.path, self.collect(target)?)
     }
 }
 // ////////////////////////////////////////////////////////////////////////////
 // ============================================================================
+/// struct Comment.
+///
+/// A comment line held back by `Replacer` until its paragraph is complete.
+#[derive(Debug)]
+struct Comment {
+    /// row.
+    row: usize,
+    /// line.
+    line: String,
+    /// eol.
+    eol: String,
+    /// body.
+    body: reflow::Line,
+    /// begin, whether the line opens a block comment.
+    begin: bool,
+}
+// ============================================================================
 /// struct Replacer.
 #[derive(Debug, Clone)]
 pub(crate) struct Replacer<'a> {
@@ -156,17 +408,17 @@ impl<'a> Replacer<'a> {
     }
     // ========================================================================
     /// `line_separator`.
-    #[expect(clippy::arithmetic_side_effects, reason = "checked")]
     fn line_separator(
         &self,
-        _lang: &Language,
+        lang: &Language,
+        limits: Limits,
         path: &Path,
         row: usize,
         line_type: &LineType,
         line: &str,
     ) -> Result<(bool, String), Error> {
-        let l = line.graphemes(true).count();
-        let c = self.config.column;
+        let l = self.config.width(lang, line);
+        let c = limits.column;
         let body = line_type.body().ok_or_else(|| {
             Error::Inspect(
                 "Inspectpr::line_separator; line_type.body()".to_owned(),
@@ -176,15 +428,13 @@ impl<'a> Replacer<'a> {
         if c < l {
             if self.ask(self.config, "* shrink?", true)? {
                 let mut s = String::from(line);
-                for _ in 0..(l - c) {
-                    let _ = s.pop().ok_or_else(|| {
-                        Error::Inspect(format!(
-                            "column79::inspector::Replacer::line_separator: \
-                             path = \"{0}\", row = {row}: \
-                             pop",
-                            path.display()
-                        ))
-                    })?;
+                if !self.shrink(lang, limits, &mut s, "") {
+                    return Err(Error::Inspect(format!(
+                        "column79::inspector::Replacer::line_separator: \
+                         path = \"{0}\", row = {row}: \
+                         pop",
+                        path.display()
+                    )));
                 }
                 Ok((true, s))
             } else {
@@ -198,15 +448,245 @@ impl<'a> Replacer<'a> {
                         .to_owned(),
                 )
             })?;
-            for _ in 0..(c - l) {
-                s.push(b);
-            }
+            self.expand(lang, limits, &mut s, b, "");
             Ok((true, s))
         } else {
             Ok((false, String::from(line)))
         }
     }
     // ========================================================================
+    /// shrink.
+    ///
+    /// Pops chars from `s` until `s` followed by `foot` fits the column.
+    fn shrink(
+        &self,
+        lang: &Language,
+        limits: Limits,
+        s: &mut String,
+        foot: &str,
+    ) -> bool {
+        while limits.column < self.config.width(lang, &format!("{s}{foot}")) {
+            if s.pop().is_none() {
+                return false;
+            }
+        }
+        true
+    }
+    // ------------------------------------------------------------------------
+    /// expand.
+    ///
+    /// Pushes `b` to `s` while `s` followed by `foot` fits the column.
+    fn expand(
+        &self,
+        lang: &Language,
+        limits: Limits,
+        s: &mut String,
+        b: char,
+        foot: &str,
+    ) {
+        let mut w = self.config.width(lang, &format!("{s}{foot}"));
+        loop {
+            s.push(b);
+            let x = self.config.width(lang, &format!("{s}{foot}"));
+            if limits.column < x || x <= w {
+                let _ = s.pop();
+                break;
+            }
+            w = x;
+        }
+    }
+    // ========================================================================
+    /// comment.
+    ///
+    /// A line comment alone on its line, or a line of a block comment
+    /// spanning multiple lines, split for reflow.  The flag tells whether
+    /// the line opens the block comment.  Modelines and a shebang on the
+    /// first `row` are never reflowed.
+    fn comment(
+        &self,
+        lang: &Language,
+        limits: Limits,
+        row: usize,
+        line: &str,
+        line_type: &LineType,
+    ) -> Option<(reflow::Line, bool)> {
+        if line_type.body().is_some_and(|x| Modeline::is_modeline(x))
+            || (1 == row && line.starts_with("#!"))
+        {
+            return None;
+        }
+        match *line_type {
+            LineType::LineComment(ref head, ref body) => {
+                let lcb = lang.re_lcb()?.find(head)?;
+                if !head[..lcb.start()].trim().is_empty() {
+                    return None;
+                }
+                let marker = format!("{0}!", lcb.as_str());
+                Some((reflow::Line::new(head, body, &marker), false))
+            }
+            LineType::BlockInner(ref head, ref body, ref foot)
+                if !foot.is_empty()
+                    || !LineType::is_separator(limits, body) =>
+            {
+                let bcb = lang.re_bcb().and_then(|x| x.find(head));
+                if bcb.is_some_and(|x| !head[..x.start()].trim().is_empty()) {
+                    return None;
+                }
+                let marker = bcb
+                    .map_or_else(String::new, |x| format!("{0}!", x.as_str()));
+                let mut line = reflow::Line::new(head, body, &marker);
+                line.foot.clone_from(foot);
+                Some((line, bcb.is_some()))
+            }
+            LineType::LineSeparator(_, _)
+            | LineType::BlockComment(_, _, _)
+            | LineType::BlockSeparator(_, _, _)
+            | LineType::BlockInner(_, _, _)
+            | LineType::Ignored
+            | LineType::Other => None,
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// reflow.
+    ///
+    /// Rewraps the paragraphs of `comments` that overflow the column and
+    /// pushes the lines to `out`.
+    fn reflow(
+        &self,
+        lang: &Language,
+        limits: Limits,
+        path: &Path,
+        comments: &mut Vec<Comment>,
+        out: &mut String,
+    ) -> Result<bool, Error> {
+        let c = limits.column;
+        let width = |s: &str| self.config.width(lang, s);
+        let lines =
+            comments.iter().map(|x| x.body.clone()).collect::<Vec<_>>();
+        let mut fixes = false;
+        for p in reflow::paragraphs(&lines) {
+            let xs = &comments[p.range.clone()];
+            let mut over = false;
+            for x in xs.iter().filter(|x| c < width(&x.line)) {
+                over = true;
+                if !self.config.flags.contains(Flags::DRYRUN) {
+                    self.println_line(path, x.row, width(&x.line), &x.line)?;
+                }
+            }
+            if p.fill && over && self.ask(self.config, "* reflow?", true)? {
+                let eol = xs
+                    .iter()
+                    .map(|x| x.eol.as_str())
+                    .find(|x| !x.is_empty())
+                    .unwrap_or("\n");
+                let last = xs.last().map_or("", |x| x.eol.as_str());
+                let ys = reflow::fill(&lines[p.range], c, &width);
+                for (i, y) in ys.iter().enumerate() {
+                    out.push_str(y);
+                    out.push_str(if i + 1 == ys.len() { last } else { eol });
+                }
+                fixes = true;
+                continue;
+            }
+            for x in xs {
+                out.push_str(&x.line);
+                out.push_str(&x.eol);
+            }
+        }
+        comments.clear();
+        Ok(fixes)
+    }
+    // ========================================================================
+    /// write.
+    ///
+    /// Writes a sibling temporary file, copies the permissions and
+    /// ownership of `path`, syncs it and renames it over `path` atomically.
+    /// A file with several hard links is rewritten in place instead, so
+    /// that the links keep sharing it; not atomically, so `inspect` backs it
+    /// up even under `Backup::None`.
+    fn write(&self, path: &Path, contents: &[u8]) -> Result<(), Error> {
+        let target = path.canonicalize()?;
+        let metadata = std::fs::metadata(&target)?;
+        let keep_mtime = self.config.flags.contains(Flags::KEEPMTIME);
+        if Self::is_hard_linked(&metadata) {
+            let mut file = OpenOptions::new().write(true).open(&target)?;
+            file.set_len(0)?;
+            file.write_all(contents)?;
+            if keep_mtime {
+                file.set_modified(metadata.modified()?)?;
+            }
+            return Ok(file.sync_all()?);
+        }
+        let dir = target.parent().ok_or_else(|| {
+            Error::Inspect(format!(
+                "column79::inspector::Replacer::write: \
+                 path = \"{0}\": parent",
+                path.display()
+            ))
+        })?;
+        let mut tmp = tempfile::Builder::new()
+            .prefix(".column79-")
+            .suffix(".tmp")
+            .tempfile_in(dir)?;
+        tmp.write_all(contents)?;
+        let file = tmp.as_file();
+        file.set_permissions(metadata.permissions())?;
+        Self::copy_owner(&metadata, file);
+        if keep_mtime {
+            file.set_modified(metadata.modified()?)?;
+        }
+        file.sync_all()?;
+        drop(tmp.persist(&target).map_err(|e| e.error)?);
+        if let Ok(x) = File::open(dir) {
+            drop(x.sync_all());
+        }
+        Ok(())
+    }
+    // ------------------------------------------------------------------------
+    /// `is_hard_linked`.
+    #[cfg(unix)]
+    fn is_hard_linked(metadata: &Metadata) -> bool {
+        1 < metadata.nlink()
+    }
+    // ------------------------------------------------------------------------
+    /// `is_hard_linked`.
+    #[cfg(not(unix))]
+    const fn is_hard_linked(_metadata: &Metadata) -> bool {
+        false
+    }
+    // ------------------------------------------------------------------------
+    /// `copy_owner`.
+    #[cfg(unix)]
+    fn copy_owner(metadata: &Metadata, file: &File) {
+        if let Err(e) =
+            fchown(file, Some(metadata.uid()), Some(metadata.gid()))
+        {
+            warn!("Replacer::copy_owner: {e}");
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// `copy_owner`.
+    #[cfg(not(unix))]
+    const fn copy_owner(_metadata: &Metadata, _file: &File) {}
+    // ========================================================================
+    /// diff.
+    ///
+    /// A unified diff from `old` to `new`, with `a/` and `b/` paths relative
+    /// to the current directory; a path outside of it without its root.
+    fn diff(path: &Path, old: &str, new: &str) -> Result<String, Error> {
+        let name = display_name(path)?
+            .components()
+            .filter(|x| {
+                !matches!(*x, Component::Prefix(_) | Component::RootDir)
+            })
+            .collect::<PathBuf>();
+        let name = name.display();
+        Ok(TextDiff::from_lines(old, new)
+            .unified_diff()
+            .header(&format!("a/{name}"), &format!("b/{name}"))
+            .to_string())
+    }
+    // ========================================================================
     /// `make_line`.
     #[expect(clippy::unwrap_used, reason = "checked")]
     fn make_line(lang: &Language, line_type: &LineType) -> String {
@@ -223,35 +703,17 @@ impl<'a> Replacer<'a> {
     }
     // ========================================================================
     /// `make_line_separator`.
-    #[expect(
-        clippy::arithmetic_side_effects,
-        clippy::cast_possible_wrap,
-        clippy::unwrap_used,
-        reason = "checked"
-    )]
+    #[expect(clippy::unwrap_used, reason = "checked")]
     fn make_line_separator(
         &self,
         lang: &Language,
+        limits: Limits,
         line_type: &LineType,
     ) -> String {
-        let c = self.config.column;
         let mut s = Self::make_line(lang, line_type);
-        let d = s.graphemes(true).count() as isize - c as isize;
-        match d.cmp(&0) {
-            Less => {
-                let b =
-                    line_type.body().unwrap().chars().rev().nth(0).unwrap();
-                for _ in 0..-d {
-                    s.push(b);
-                }
-            }
-            Greater => {
-                for _ in 0..d {
-                    let _ = s.pop();
-                }
-            }
-            Equal => {}
-        }
+        let b = line_type.body().unwrap().chars().rev().nth(0).unwrap();
+        let _ = self.shrink(lang, limits, &mut s, "");
+        self.expand(lang, limits, &mut s, b, "");
         s
     }
     // ========================================================================
@@ -264,7 +726,9 @@ impl<'a> Replacer<'a> {
         line_type: &LineType,
         line: &str,
     ) -> Result<(bool, String), Error> {
-        if self.ask(self.config, "* convert to line comment?", true)? {
+        if lang.has_line_comment()
+            && self.ask(self.config, "* convert to line comment?", true)?
+        {
             let s = Self::make_line(lang, line_type);
             Ok((true, s))
         } else {
@@ -273,28 +737,25 @@ impl<'a> Replacer<'a> {
     }
     // ========================================================================
     /// `block_separator`.
-    #[expect(
-        clippy::arithmetic_side_effects,
-        clippy::unwrap_used,
-        reason = "checked"
-    )]
+    #[expect(clippy::unwrap_used, reason = "checked")]
     fn block_separator(
         &self,
         lang: &Language,
+        limits: Limits,
         path: &Path,
         row: usize,
         line_type: &LineType,
         line: &str,
     ) -> Result<(bool, String), Error> {
-        let l = line.graphemes(true).count();
-        let c = self.config.column;
+        let l = self.config.width(lang, line);
+        let c = limits.column;
         let has_line = lang.has_line_comment();
         let body = line_type.body().unwrap();
         if c == l {
             if has_line
                 && self.ask(self.config, "* convert to line comment?", true)?
             {
-                let s = self.make_line_separator(lang, line_type);
+                let s = self.make_line_separator(lang, limits, line_type);
                 Ok((true, s))
             } else {
                 Ok((false, String::from(line)))
@@ -307,22 +768,21 @@ impl<'a> Replacer<'a> {
                     true,
                 )?
             {
-                let s = self.make_line_separator(lang, line_type);
+                let s = self.make_line_separator(lang, limits, line_type);
                 Ok((true, s))
             } else if self.ask(self.config, "* shrink?", true)? {
                 let mut s = line_type.head().unwrap().clone();
                 s.push_str(body);
-                for _ in 0..(l - c) {
-                    let _ = s.pop().ok_or_else(|| {
-                        Error::Inspect(format!(
-                            "column79::inspector::Replacer::block_separator : \
-                             path = \"{0}\", row = {row}: \
-                             pop",
-                            path.display()
-                        ))
-                    })?;
+                let foot = line_type.foot().unwrap();
+                if !self.shrink(lang, limits, &mut s, foot) {
+                    return Err(Error::Inspect(format!(
+                        "column79::inspector::Replacer::block_separator : \
+                         path = \"{0}\", row = {row}: \
+                         pop",
+                        path.display()
+                    )));
                 }
-                s.push_str(line_type.foot().unwrap());
+                s.push_str(foot);
                 Ok((true, s))
             } else {
                 Ok((false, String::from(line)))
@@ -334,16 +794,15 @@ impl<'a> Replacer<'a> {
                 true,
             )?
         {
-            let s = self.make_line_separator(lang, line_type);
+            let s = self.make_line_separator(lang, limits, line_type);
             Ok((true, s))
         } else if self.ask(self.config, "* expand?", true)? {
             let mut s = line_type.head().unwrap().clone();
             s.push_str(body);
             let b = body.chars().rev().nth(0).unwrap();
-            for _ in 0..(c - l) {
-                s.push(b);
-            }
-            s.push_str(line_type.foot().unwrap());
+            let foot = line_type.foot().unwrap();
+            self.expand(lang, limits, &mut s, b, foot);
+            s.push_str(foot);
             Ok((true, s))
         } else {
             Ok((false, String::from(line)))
@@ -354,58 +813,352 @@ impl<'a> Replacer<'a> {
 impl Inspector for Replacer<'_> {
     // ========================================================================
     /// inspect.
-    #[expect(
-        clippy::unwrap_used,
-        clippy::unwrap_in_result,
-        reason = "checked"
-    )]
-    fn inspect(&self, lang: &Language, path: &Path) -> Result<(), Error> {
-        let c = self.config.column;
-        let mut file_tmp = tempfile()?;
-        let mut ftmp = BufWriter::new(&mut file_tmp);
+    fn inspect(&self, target: &Target<'_>) -> Result<(), Error> {
+        let path = target.path.as_path();
+        let old = std::fs::read_to_string(path)?;
+        let (lang, limits) =
+            self.config.resolve(target.language, &target.over, &old);
+        let c = limits.column;
+        let dry_run = self.config.flags.contains(Flags::DRYRUN);
+        let mut out = String::new();
         let mut fixes = false;
-        self.inspect_impl(self.config, lang, path, &mut |row, l_type, l| {
-            let (f, mut s) = if self.check_type(lang, c, l_type, l) {
+        let mut comments = Vec::<Comment>::new();
+        self.inspect_impl(limits, lang, &old, &mut |row, l_type, l, eol| {
+            if let Some((x, begin)) =
+                self.comment(lang, limits, row, l, l_type)
+            {
+                if let Some(last) = comments.last_mut()
+                    && last.begin
+                    && !begin
+                {
+                    last.body.cont.clone_from(&x.prefix);
+                }
+                comments.push(Comment {
+                    row,
+                    line: l.to_owned(),
+                    eol: eol.to_owned(),
+                    body: x,
+                    begin,
+                });
+                return Ok(());
+            }
+            fixes |=
+                self.reflow(lang, limits, path, &mut comments, &mut out)?;
+            let width = self.config.width(lang, l);
+            let (f, s) = if self.check_type(lang, c, l_type, width) {
                 (false, String::from(l))
             } else {
-                drop(self.println_line(path, row, l));
+                if !dry_run {
+                    drop(self.println_line(path, row, width, l));
+                }
                 match *l_type {
                     LineType::LineSeparator(_, _) => {
-                        self.line_separator(lang, path, row, l_type, l)
+                        self.line_separator(lang, limits, path, row, l_type, l)
                     }
                     LineType::BlockComment(_, _, _) => {
                         self.block_comment(lang, path, row, l_type, l)
                     }
-                    LineType::BlockSeparator(_, _, _) => {
-                        self.block_separator(lang, path, row, l_type, l)
+                    LineType::BlockSeparator(_, _, _) => self
+                        .block_separator(lang, limits, path, row, l_type, l),
+                    LineType::BlockInner(_, ref body, ref foot)
+                        if foot.is_empty()
+                            && LineType::is_separator(limits, body) =>
+                    {
+                        self.line_separator(lang, limits, path, row, l_type, l)
                     }
-                    LineType::LineComment(_, _) | LineType::Other => {
+                    LineType::BlockInner(_, _, _) => {
                         Ok((false, String::from(l)))
                     }
+                    LineType::LineComment(_, _)
+                    | LineType::Ignored
+                    | LineType::Other => Ok((false, String::from(l))),
                 }?
             };
-            s.push('\n');
-            let _ = ftmp.write(s.as_ref())?;
+            out.push_str(&s);
+            out.push_str(eol);
             fixes |= f;
             Ok(())
         })?;
-        if fixes {
-            let file_tmp_x = ftmp.into_inner().unwrap();
-            let _ = file_tmp_x.seek(SeekFrom::Start(0))?;
-            let mut ftmp_x = BufReader::new(file_tmp_x);
-            {
-                // backup
-                let mut extension = path.extension().unwrap().to_os_string();
-                extension.push(".backup");
-                let path_back = path.with_extension(extension);
-                println!("* backup: {}", path_back.display());
-                std::fs::rename(path, path_back)?;
+        fixes |= self.reflow(lang, limits, path, &mut comments, &mut out)?;
+        if !fixes {
+            return Ok(());
+        }
+        if old.starts_with(BOM) {
+            out.insert_str(0, BOM);
+        }
+        if dry_run {
+            print!("{}", Self::diff(path, &old, &out)?);
+            return Ok(());
+        }
+        let mut backup = self.config.backup;
+        if Backup::None == backup
+            && Self::is_hard_linked(&std::fs::metadata(path)?)
+        {
+            warn!(
+                "Replacer::inspect: {:?} is hard linked, backed up anyway",
+                path.display()
+            );
+            backup = Backup::Sibling;
+        }
+        if let Some(path_back) = backup.path(
+            path,
+            &self.config.relative(path),
+            &self.config.root.join(&self.config.backup_dir),
+            &self.config.backup_suffix,
+        ) {
+            if let Some(dir) = path_back.parent() {
+                std::fs::create_dir_all(dir)?;
             }
-            let mut file_new = File::create(path)?;
-            let mut fnew = BufWriter::new(&mut file_new);
-            let _ = std::io::copy(&mut ftmp_x, &mut fnew)?;
-            println!("* replace: {}", path.display());
+            println!("* backup: {}", path_back.display());
+            let _ = std::fs::copy(path, path_back)?;
         }
+        self.write(path, out.as_ref())?;
+        println!("* replace: {}", path.display());
         Ok(())
     }
 }
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    // use  ===================================================================
+    use alloc::collections::BTreeMap;
+    use std::{
+        fs::FileTimes,
+        io::Write as _,
+        os::unix::fs::{MetadataExt as _, PermissionsExt as _},
+        path::Path,
+        process::{Command, Stdio},
+        time::{Duration, SystemTime},
+    };
+    // ------------------------------------------------------------------------
+    use super::{BOM, Checker, Inspector as _, Replacer, Target};
+    use crate::{
+        backup::Backup,
+        config::{Config, Limits, Override},
+        flags::Flags,
+        language::{Language, LanguageSrc},
+        report::Violation,
+    };
+    // ========================================================================
+    fn sh() -> Language {
+        let src: LanguageSrc = toml::from_str(
+            r##"
+            name = "sh"
+            line_comment_begin = "#"
+            "##,
+        )
+        .expect("LanguageSrc");
+        Language::from_src(src, &BTreeMap::new()).expect("sh")
+    }
+    // ------------------------------------------------------------------------
+    const fn limits() -> Limits {
+        Limits {
+            column: 79,
+            separator_threshold: 12,
+        }
+    }
+    // ------------------------------------------------------------------------
+    fn lines(text: &str) -> Vec<(usize, String, String)> {
+        let config = Config::default();
+        let mut ret = Vec::new();
+        Replacer::new(&config)
+            .inspect_impl(limits(), &sh(), text, &mut |row, _, l, eol| {
+                ret.push((row, l.to_owned(), eol.to_owned()));
+                Ok(())
+            })
+            .expect("inspect_impl");
+        ret
+    }
+    // ------------------------------------------------------------------------
+    fn join(lines: &[(usize, String, String)]) -> String {
+        lines
+            .iter()
+            .map(|(_, l, eol)| format!("{l}{eol}"))
+            .collect()
+    }
+    // ========================================================================
+    #[test]
+    fn eol() {
+        for text in ["a\r\nb\r\n", "a\nb\r\nc\n", "a\nb", "", "\n\r\n"] {
+            assert_eq!(join(&lines(text)), text);
+        }
+        let x = lines("a\r\nb\nc");
+        assert_eq!(
+            x.iter()
+                .map(|(row, l, eol)| (*row, l.as_str(), eol.as_str()))
+                .collect::<Vec<_>>(),
+            [(1, "a", "\r\n"), (2, "b", "\n"), (3, "c", "")]
+        );
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn bom() {
+        let text = format!("{BOM}a\r\nb");
+        let x = lines(&text);
+        assert_eq!(x[0].1, "a");
+        assert_eq!(join(&x), text.strip_prefix(BOM).expect("BOM"));
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn shebang() {
+        let config = Config::default();
+        let replacer = Replacer::new(&config);
+        let lang = sh();
+        let mut comments = Vec::new();
+        replacer
+            .inspect_impl(
+                limits(),
+                &lang,
+                "#!/bin/sh -e\n#!-long\n",
+                &mut |row, l_type, l, _| {
+                    comments.push(
+                        replacer
+                            .comment(&lang, limits(), row, l, l_type)
+                            .is_some(),
+                    );
+                    Ok(())
+                },
+            )
+            .expect("inspect_impl");
+        assert_eq!(comments, [false, true]);
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn baseline() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path().to_path_buf();
+        let baseline = root.join("baseline.json");
+        let violation = |path: &str, line: &str| Violation {
+            path: path.to_owned(),
+            row: 1,
+            width: 80,
+            column: 79,
+            line_type: "other",
+            language: "sh".to_owned(),
+            line: line.to_owned(),
+        };
+        let mut config = Config::default();
+        config.root.clone_from(&root);
+        config.current.clone_from(&root);
+        config.write_baseline = Some(baseline.clone());
+        let checker = Checker::new(&config, None).expect("Checker::new");
+        checker
+            .emit(
+                Path::new("src/a.sh"),
+                Some(vec![violation("src/a.sh", "a")]),
+            )
+            .expect("emit");
+        let _ = checker.finish().expect("finish");
+        config.current = root.join("src");
+        config.write_baseline = None;
+        config.baseline = Some(baseline);
+        let checker = Checker::new(&config, None).expect("Checker::new");
+        checker
+            .emit(
+                Path::new("a.sh"),
+                Some(vec![violation("a.sh", "a"), violation("a.sh", "b")]),
+            )
+            .expect("emit");
+        assert_eq!(checker.finish().expect("finish").violations(), 1);
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn dry_run() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("a.sh");
+        let old = format!("echo a\n#{0}\necho b\n", "=".repeat(99));
+        std::fs::write(&path, &old).expect("write");
+        let lang = sh();
+        let target = Target {
+            language: &lang,
+            over: Override::default(),
+            path: path.clone(),
+        };
+        let mut config = Config::default();
+        config.backup = Backup::None;
+        config.flags = Flags::DRYRUN;
+        Replacer::new(&config).inspect(&target).expect("dry run");
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), old);
+        assert_eq!(std::fs::read_dir(dir.path()).expect("dir").count(), 1);
+        config.flags = Flags::NOASK;
+        Replacer::new(&config).inspect(&target).expect("inspect");
+        let new = std::fs::read_to_string(&path).expect("read");
+        assert_eq!(new, format!("echo a\n#{0}\necho b\n", "=".repeat(78)));
+        let diff = Replacer::diff(&path, &old, &new).expect("diff");
+        assert!(diff.starts_with("--- a/"), "{diff}");
+        assert!(diff.contains("\n+++ b/"), "{diff}");
+        assert!(diff.contains("\n@@ -1,3 +1,3 @@\n"), "{diff}");
+        std::fs::write(&path, &old).expect("write");
+        let mut git = Command::new("git")
+            .args(["apply", "-"])
+            .current_dir("/")
+            .stdin(Stdio::piped())
+            .spawn()
+            .expect("git apply");
+        git.stdin
+            .take()
+            .expect("stdin")
+            .write_all(diff.as_bytes())
+            .expect("write_all");
+        assert!(git.wait().expect("wait").success());
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), new);
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn write() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("a.sh");
+        let link = dir.path().join("b.sh");
+        let past = SystemTime::UNIX_EPOCH + Duration::from_secs(1 << 30);
+        let reset = || {
+            std::fs::write(&path, "a\n").expect("write");
+            std::fs::set_permissions(
+                &path,
+                std::fs::Permissions::from_mode(0o750),
+            )
+            .expect("set_permissions");
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .expect("open")
+                .set_times(FileTimes::new().set_modified(past))
+                .expect("set_times");
+        };
+        let mut config = Config::default();
+        let modified = |path: &Path| {
+            std::fs::metadata(path)
+                .expect("metadata")
+                .modified()
+                .expect("modified")
+        };
+        // atomic rename
+        reset();
+        let ino = std::fs::metadata(&path).expect("metadata").ino();
+        Replacer::new(&config).write(&path, b"b\n").expect("write");
+        let metadata = std::fs::metadata(&path).expect("metadata");
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), "b\n");
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o750);
+        assert_ne!(metadata.ino(), ino);
+        assert_ne!(modified(&path), past);
+        assert_eq!(std::fs::read_dir(dir.path()).expect("dir").count(), 1);
+        config.flags = Flags::KEEPMTIME;
+        reset();
+        Replacer::new(&config).write(&path, b"c\n").expect("write");
+        assert_eq!(modified(&path), past);
+        // in place
+        std::fs::hard_link(&path, &link).expect("hard_link");
+        for (flags, expected) in
+            [(Flags::KEEPMTIME, true), (Flags::empty(), false)]
+        {
+            config.flags = flags;
+            reset();
+            Replacer::new(&config).write(&path, b"d\n").expect("write");
+            assert_eq!(std::fs::read_to_string(&link).expect("read"), "d\n");
+            let metadata = std::fs::metadata(&path).expect("metadata");
+            assert_eq!(metadata.nlink(), 2);
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o750);
+            assert_eq!(modified(&path) == past, expected);
+        }
+    }
+}
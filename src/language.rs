@@ -6,13 +6,14 @@
 //  @author hanepjiv <hanepjiv@gmail.com>
 //  @copyright The MIT License (MIT) / Apache License Version 2.0
 //  @since 2016/10/13
-//  @date 2026/03/01
+//  @date 2026/10/18
 
 // ////////////////////////////////////////////////////////////////////////////
 // use  =======================================================================
-use alloc::collections::BTreeMap;
-use core::cell::RefCell;
+use alloc::{borrow::Cow, collections::BTreeMap};
+use std::{ffi::OsStr, fs::File, io::Read as _, path::Path};
 // ----------------------------------------------------------------------------
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use regex::{Captures, Regex};
 use serde::Deserialize;
 // ----------------------------------------------------------------------------
@@ -20,7 +21,7 @@ use crate::error::Error;
 // ////////////////////////////////////////////////////////////////////////////
 // ============================================================================
 /// struct `LanguageSrc`
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub(crate) struct LanguageSrc {
     /// name
     pub name: Option<String>,
@@ -28,15 +29,63 @@ pub(crate) struct LanguageSrc {
     pub base: Option<String>,
     /// extensions
     pub extensions: Option<Vec<String>>,
+    /// filenames, whole file names such as `Makefile`.
+    pub filenames: Option<Vec<String>>,
+    /// globs, matched against the file name and the path.
+    pub globs: Option<Vec<String>>,
+    /// shebangs, interpreter names such as `sh`, also with a version suffix.
+    pub shebangs: Option<Vec<String>>,
+    /// modes, Emacs modes and Vim filetypes naming this in modelines.
+    pub modes: Option<Vec<String>>,
     /// `line_comment_begin`
     pub line_comment_begin: Option<String>,
     /// `block_comment_begin`
     pub block_comment_begin: Option<String>,
     /// `block_comment_end`
     pub block_comment_end: Option<String>,
+    /// `string_literals`, regexes matching a whole string or char literal.
+    pub string_literals: Option<Vec<String>>,
+    /// `tab_width`
+    pub tab_width: Option<usize>,
+    /// column
+    pub column: Option<usize>,
+    /// `separator_threshold`
+    pub separator_threshold: Option<usize>,
     /// sublanguages
     pub sublanguages: Option<Vec<String>>,
 }
+// ============================================================================
+impl LanguageSrc {
+    // ========================================================================
+    /// layer
+    ///
+    /// Overrides the fields given in `src`, a later entry of the same name.
+    pub(crate) fn layer(&mut self, src: Self) {
+        self.base = src.base.or_else(|| self.base.take());
+        self.extensions = src.extensions.or_else(|| self.extensions.take());
+        self.filenames = src.filenames.or_else(|| self.filenames.take());
+        self.globs = src.globs.or_else(|| self.globs.take());
+        self.shebangs = src.shebangs.or_else(|| self.shebangs.take());
+        self.modes = src.modes.or_else(|| self.modes.take());
+        self.line_comment_begin = src
+            .line_comment_begin
+            .or_else(|| self.line_comment_begin.take());
+        self.block_comment_begin = src
+            .block_comment_begin
+            .or_else(|| self.block_comment_begin.take());
+        self.block_comment_end = src
+            .block_comment_end
+            .or_else(|| self.block_comment_end.take());
+        self.string_literals =
+            src.string_literals.or_else(|| self.string_literals.take());
+        self.tab_width = src.tab_width.or(self.tab_width);
+        self.column = src.column.or(self.column);
+        self.separator_threshold =
+            src.separator_threshold.or(self.separator_threshold);
+        self.sublanguages =
+            src.sublanguages.or_else(|| self.sublanguages.take());
+    }
+}
 // ////////////////////////////////////////////////////////////////////////////
 // ============================================================================
 /// struct Language
@@ -48,18 +97,42 @@ pub(crate) struct Language {
     base: Option<String>,
     /// extensions
     extensions: Vec<String>,
+    /// filenames
+    filenames: Vec<String>,
+    /// `glob_set`
+    glob_set: GlobSet,
+    /// shebangs
+    shebangs: Vec<String>,
+    /// modes
+    modes: Vec<String>,
     /// `line_comment_begin`
     line_comment_begin: Option<String>,
     /// `block_comment_begin`
     block_comment_begin: Option<String>,
     /// `block_comment_end`
     block_comment_end: Option<String>,
+    /// `string_literals`
+    string_literals: Option<Vec<String>>,
+    /// `tab_width`
+    tab_width: Option<usize>,
+    /// column
+    column: Option<usize>,
+    /// `separator_threshold`
+    separator_threshold: Option<usize>,
     /// sublanguages
     sublanguages: Vec<String>,
     /// `re_line`
-    re_line: RefCell<Option<Regex>>,
+    re_line: Option<Regex>,
     /// `re_block`
-    re_block: RefCell<Option<Regex>>,
+    re_block: Option<Regex>,
+    /// `re_lcb`
+    re_lcb: Option<Regex>,
+    /// `re_bcb`
+    re_bcb: Option<Regex>,
+    /// `re_bce`
+    re_bce: Option<Regex>,
+    /// `re_literal`
+    re_literal: Option<Regex>,
 }
 // ============================================================================
 impl Language {
@@ -73,6 +146,15 @@ impl Language {
     pub(crate) const fn peek_bcb(&self) -> Option<&String> {
         self.block_comment_begin.as_ref()
     }
+    pub(crate) const fn peek_tab_width(&self) -> Option<usize> {
+        self.tab_width
+    }
+    pub(crate) const fn peek_column(&self) -> Option<usize> {
+        self.column
+    }
+    pub(crate) const fn peek_separator_threshold(&self) -> Option<usize> {
+        self.separator_threshold
+    }
     /*
     pub(crate) fn peek_bce(&self)  -> &Option<String>  {
     &self.block_comment_end
@@ -104,6 +186,18 @@ impl Language {
         {
             self.block_comment_end.clone_from(&base.block_comment_end);
         }
+        if self.string_literals.is_none() && base.string_literals.is_some() {
+            self.string_literals.clone_from(&base.string_literals);
+        }
+        if self.tab_width.is_none() {
+            self.tab_width = base.tab_width;
+        }
+        if self.column.is_none() {
+            self.column = base.column;
+        }
+        if self.separator_threshold.is_none() {
+            self.separator_threshold = base.separator_threshold;
+        }
     }
     // ========================================================================
     #[expect(clippy::unwrap_used, reason = "checked")]
@@ -146,9 +240,29 @@ impl Language {
         if let Some(x) = src.extensions {
             ret.extensions = x;
         }
+        if let Some(x) = src.filenames {
+            ret.filenames = x;
+        }
+        if let Some(x) = src.globs {
+            let mut builder = GlobSetBuilder::new();
+            for i in &x {
+                let _ = builder.add(Glob::new(i)?);
+            }
+            ret.glob_set = builder.build()?;
+        }
+        if let Some(x) = src.shebangs {
+            ret.shebangs = x;
+        }
+        if let Some(x) = src.modes {
+            ret.modes = x;
+        }
         ret.line_comment_begin = src.line_comment_begin;
         ret.block_comment_begin = src.block_comment_begin;
         ret.block_comment_end = src.block_comment_end;
+        ret.string_literals = src.string_literals;
+        ret.tab_width = src.tab_width;
+        ret.column = src.column;
+        ret.separator_threshold = src.separator_threshold;
         if let Some(x) = src.sublanguages {
             ret.sublanguages = x;
         }
@@ -160,89 +274,449 @@ impl Language {
             ret.extend(languages.get(&base).unwrap());
         }
 
+        ret.compile()?;
+
         Ok(ret)
     }
+    // ------------------------------------------------------------------------
+    /// compile
+    ///
+    /// Compiles the comment markers and `string_literals` once, so that an
+    /// invalid pattern is an `Error::InvalidConfig` instead of a panic in
+    /// the middle of an inspection.
+    fn compile(&mut self) -> Result<(), Error> {
+        let name = &self.name;
+        let re = |key: &str, pattern: &str| {
+            Regex::new(pattern).map_err(|e| {
+                Error::InvalidConfig(format!(
+                    "::column79::language::Language::compile: \
+                     name = \"{name}\", {key}: {e}"
+                ))
+            })
+        };
+        let lcb = self.line_comment_begin.as_deref();
+        let bcb = self.block_comment_begin.as_deref();
+        let bce = self.block_comment_end.as_deref();
+        self.re_lcb = lcb.map(|x| re("line_comment_begin", x)).transpose()?;
+        self.re_bcb = bcb.map(|x| re("block_comment_begin", x)).transpose()?;
+        self.re_bce = bce.map(|x| re("block_comment_end", x)).transpose()?;
+        self.re_line = lcb
+            .map(|x| re("line_comment_begin", &format!(r"^(.*?{x}\s*)(.*)$")))
+            .transpose()?;
+        self.re_block = bcb
+            .zip(bce)
+            .map(|(b, e)| {
+                re(
+                    "block_comment_begin",
+                    &format!(r"^(.*?{b}\s*)(.*?)(\s*{e})$"),
+                )
+            })
+            .transpose()?;
+        self.re_literal = self
+            .string_literals
+            .as_ref()
+            .map(|xs| {
+                let pattern = xs
+                    .iter()
+                    .map(|i| format!("(?:{i})"))
+                    .collect::<Vec<_>>()
+                    .join("|");
+                re("string_literals", &format!(r"\A(?:{pattern})"))
+            })
+            .transpose()?;
+        Ok(())
+    }
     // ========================================================================
-    #[expect(clippy::expect_used, reason = "checked")]
     pub(crate) fn re_line_captures<'t>(
         &self,
         line: &'t str,
     ) -> Option<Captures<'t>> {
-        if let Some(ref lcb) = self.line_comment_begin {
-            if let Some(ref mut re) = *self.re_line.borrow_mut() {
-                return re.captures(line);
-            }
-            let re = Regex::new(&format!(r"^(.*?{lcb}\s*)(.*)$"))
-                .expect("re_line_captures");
-            let ret = re.captures(line);
-            *self.re_line.borrow_mut() = Some(re);
-            ret
-        } else {
-            None
-        }
+        self.re_line.as_ref()?.captures(line)
     }
     // ------------------------------------------------------------------------
-    #[expect(clippy::expect_used, reason = "checked")]
     pub(crate) fn re_block_captures<'t>(
         &self,
         line: &'t str,
     ) -> Option<Captures<'t>> {
-        if let Some(ref bcb) = self.block_comment_begin {
-            if let Some(ref bce) = self.block_comment_end {
-                if let Some(ref mut re) = *self.re_block.borrow_mut() {
-                    return re.captures(line);
-                }
-                let re =
-                    Regex::new(&format!(r"^(.*?{bcb}\s*)(.*?)(\s*{bce})$"))
-                        .expect("re_block_captures");
-                let ret = re.captures(line);
-                *self.re_block.borrow_mut() = Some(re);
-                ret
-            } else {
-                None
+        self.re_block.as_ref()?.captures(line)
+    }
+    // ========================================================================
+    pub(crate) const fn re_lcb(&self) -> Option<&Regex> {
+        self.re_lcb.as_ref()
+    }
+    // ------------------------------------------------------------------------
+    pub(crate) const fn re_bcb(&self) -> Option<&Regex> {
+        self.re_bcb.as_ref()
+    }
+    // ------------------------------------------------------------------------
+    pub(crate) const fn re_bce(&self) -> Option<&Regex> {
+        self.re_bce.as_ref()
+    }
+    // ------------------------------------------------------------------------
+    /// `re_literal`, the `string_literals` anchored at the haystack start.
+    pub(crate) const fn re_literal(&self) -> Option<&Regex> {
+        self.re_literal.as_ref()
+    }
+    // ========================================================================
+    /// `mask_literals`
+    ///
+    /// Replaces the string literals of `line` before the first comment
+    /// marker with NUL bytes, keeping every byte offset of `line`.  The
+    /// first `start` bytes are the rest of a literal opened on a previous
+    /// line.
+    ///
+    /// `text` is `line` followed by the rest of the text, which a literal
+    /// opened on `line` runs into; then also returns the length of `text`
+    /// after that literal.
+    pub(crate) fn mask_literals<'t>(
+        &self,
+        line: &'t str,
+        text: &str,
+        start: usize,
+    ) -> (Cow<'t, str>, Option<usize>) {
+        let mut masked = Cow::Borrowed(line);
+        if 0 < start {
+            masked.to_mut().replace_range(..start, &"\0".repeat(start));
+        }
+        let Some(re) = self.re_literal() else {
+            return (masked, None);
+        };
+        let next_marker = |pos| {
+            [self.re_lcb(), self.re_bcb()]
+                .into_iter()
+                .flatten()
+                .filter_map(|x| x.find_at(line, pos))
+                .map(|m| m.start())
+                .min()
+                .unwrap_or(line.len())
+        };
+        let mut pos = start;
+        let mut marker = next_marker(pos);
+        while let Some(c) = line[pos..marker].chars().next() {
+            let Some(m) = re.find(&text[pos..]).filter(|m| !m.is_empty())
+            else {
+                pos += c.len_utf8();
+                continue;
+            };
+            let end = pos + m.end();
+            if line.len() < end {
+                let len = line.len() - pos;
+                masked.to_mut().replace_range(pos.., &"\0".repeat(len));
+                return (masked, Some(text.len() - end));
             }
-        } else {
-            None
+            masked
+                .to_mut()
+                .replace_range(pos..end, &"\0".repeat(end - pos));
+            pos = end;
+            marker = next_marker(pos);
         }
+        (masked, None)
     }
     // ========================================================================
-    #[expect(
-        clippy::unwrap_used,
-        clippy::unwrap_in_result,
-        reason = "checked"
-    )]
-    pub(crate) fn check_path_<'a>(
+    /// `check_name`
+    ///
+    /// Whether `path` matches `filenames`, `globs` or `extensions`.
+    fn check_name(&self, path: &Path) -> bool {
+        let name = path.file_name();
+        name.and_then(OsStr::to_str)
+            .is_some_and(|x| self.filenames.iter().any(|f| f == x))
+            || self.glob_set.is_match(path)
+            || name.is_some_and(|x| self.glob_set.is_match(x))
+            || path
+                .extension()
+                .and_then(OsStr::to_str)
+                .is_some_and(|x| self.extensions.iter().any(|e| e == x))
+    }
+    // ------------------------------------------------------------------------
+    /// `check_shebang`
+    ///
+    /// Whether `interpreter` is one of `shebangs`, maybe with a version
+    /// suffix such as `python3.12`.
+    fn check_shebang(&self, interpreter: &str) -> bool {
+        self.shebangs.iter().any(|x| {
+            interpreter.strip_prefix(x.as_str()).is_some_and(|rest| {
+                rest.chars().all(|c| c.is_ascii_digit() || '.' == c)
+            })
+        })
+    }
+    // ------------------------------------------------------------------------
+    /// `check_mode`
+    pub(crate) fn check_mode(&self, mode: &str) -> bool {
+        self.modes.iter().any(|x| x == mode)
+    }
+    // ------------------------------------------------------------------------
+    /// find
+    ///
+    /// `self` or the first of its sublanguages, depth first, satisfying
+    /// `pred`.
+    fn find<'a>(
         &'a self,
-        p: &std::path::PathBuf,
-        ls: &'a BTreeMap<String, Self>,
+        languages: &'a BTreeMap<String, Self>,
+        pred: &dyn Fn(&Self) -> bool,
     ) -> Option<&'a Self> {
-        for i in &self.sublanguages {
-            if let x @ Some(_) = ls.get(i).unwrap().check_path(p, ls) {
-                return x;
-            }
+        if pred(self) {
+            return Some(self);
         }
-        None
+        self.sublanguages
+            .iter()
+            .filter_map(|i| languages.get(i))
+            .find_map(|x| x.find(languages, pred))
     }
     // ------------------------------------------------------------------------
+    /// `check_path`
+    ///
+    /// Detects the language of `path` by its name first, and then by the
+    /// shebang line if some language of the tree has `shebangs`.
     pub(crate) fn check_path<'a>(
         &'a self,
-        path: &std::path::PathBuf,
+        path: &Path,
         languages: &'a BTreeMap<String, Self>,
     ) -> Option<&'a Self> {
-        path.extension().map_or_else(
-            || self.check_path_(path, languages),
-            |ext| {
-                ext.to_os_string().into_string().as_ref().map_or_else(
-                    |_| self.check_path_(path, languages),
-                    |s| {
-                        if self.extensions.contains(s) {
-                            Some(self)
-                        } else {
-                            self.check_path_(path, languages)
-                        }
-                    },
-                )
+        self.check_head(path, languages, || {
+            let mut head = Vec::new();
+            let _ = File::open(path)
+                .ok()?
+                .take(256)
+                .read_to_end(&mut head)
+                .ok()?;
+            Some(String::from_utf8_lossy(&head).into_owned().into())
+        })
+    }
+    // ------------------------------------------------------------------------
+    /// `check_text`
+    ///
+    /// `check_path` with the shebang line read from `text`, the content of
+    /// `path` not on disk such as the standard input.
+    pub(crate) fn check_text<'a>(
+        &'a self,
+        path: &Path,
+        text: &str,
+        languages: &'a BTreeMap<String, Self>,
+    ) -> Option<&'a Self> {
+        self.check_head(path, languages, || Some(text.into()))
+    }
+    // ------------------------------------------------------------------------
+    /// `check_head`
+    fn check_head<'a, 't>(
+        &'a self,
+        path: &Path,
+        languages: &'a BTreeMap<String, Self>,
+        head: impl FnOnce() -> Option<Cow<'t, str>>,
+    ) -> Option<&'a Self> {
+        self.find(languages, &|x| x.check_name(path)).or_else(|| {
+            let _ = self.find(languages, &|x| !x.shebangs.is_empty())?;
+            let head = head()?;
+            let interpreter = Self::interpreter(&head)?;
+            self.find(languages, &|x| x.check_shebang(interpreter))
+        })
+    }
+    // ------------------------------------------------------------------------
+    /// interpreter
+    ///
+    /// The interpreter name of the shebang line at the head of a file, seen
+    /// through `env`, such as `python3` of `#!/usr/bin/env -S python3 -u`.
+    fn interpreter(head: &str) -> Option<&str> {
+        let line = head.strip_prefix("#!")?.lines().next()?;
+        let mut words = line
+            .split_whitespace()
+            .map(|x| x.rsplit_once('/').map_or(x, |(_, name)| name));
+        let first = words.next()?;
+        if "env" != first {
+            return Some(first);
+        }
+        words.find(|x| !x.starts_with('-') && !x.contains('='))
+    }
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    // use  ===================================================================
+    use alloc::collections::BTreeMap;
+    use std::path::Path;
+    // ------------------------------------------------------------------------
+    use super::{Language, LanguageSrc};
+    use crate::Error;
+    // ========================================================================
+    fn rust() -> Language {
+        Language::from_src(
+            LanguageSrc {
+                name: Some("rust".to_owned()),
+                base: None,
+                extensions: Some(vec!["rs".to_owned()]),
+                filenames: None,
+                globs: None,
+                shebangs: None,
+                modes: None,
+                line_comment_begin: Some("//".to_owned()),
+                block_comment_begin: Some(r"/\*".to_owned()),
+                block_comment_end: Some(r"\*/".to_owned()),
+                string_literals: Some(vec![
+                    r##"r#"(?s:.*?)"#"##.to_owned(),
+                    r#""(?:[^"\\]|\\.)*""#.to_owned(),
+                ]),
+                tab_width: None,
+                column: None,
+                separator_threshold: None,
+                sublanguages: None,
             },
+            &BTreeMap::new(),
+        )
+        .expect("rust")
+    }
+    // ========================================================================
+    #[test]
+    const fn sync() {
+        const fn assert_sync<T>()
+        where
+            T: Send + Sync,
+        {
+        }
+        assert_sync::<Language>();
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn mask_literals() {
+        let lang = rust();
+        let line = r#"let u = "http://a"; // c"#;
+        let (masked, open) = lang.mask_literals(line, line, 0);
+        assert_eq!(masked.len(), line.len());
+        assert_eq!(masked.find("//"), line.rfind("//"));
+        assert_eq!(open, None);
+        let line = r##"let r = r#"/* "x" */"#;"##;
+        assert!(!lang.mask_literals(line, line, 0).0.contains("/*"));
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn mask_literals_after_comment() {
+        let lang = rust();
+        let line = r#"f(); // say "hello""#;
+        assert_eq!(lang.mask_literals(line, line, 0), (line.into(), None));
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn mask_literals_multiline() {
+        let lang = rust();
+        let text = "let s = \"a\n// b\n\"; // c\n";
+        let (masked, open) = lang.mask_literals("let s = \"a", text, 0);
+        assert_eq!(masked, "let s = \0\0");
+        assert_eq!(open, Some(text.len() - text.find("; //").expect("end")));
+        let line = "\"; // c";
+        let (masked, open) = lang.mask_literals(line, line, 1);
+        assert_eq!(masked, "\0; // c");
+        assert_eq!(open, None);
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn interpreter() {
+        assert_eq!(Language::interpreter("#!/bin/sh\n"), Some("sh"));
+        assert_eq!(Language::interpreter("#! /bin/bash -e"), Some("bash"));
+        assert_eq!(
+            Language::interpreter("#!/usr/bin/env -S A=1 python3 -u\n"),
+            Some("python3")
+        );
+        assert_eq!(Language::interpreter("#!/usr/bin/env"), None);
+        assert_eq!(Language::interpreter("# sh"), None);
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn check_path() {
+        let mut languages = BTreeMap::new();
+        for i in [
+            r#"name = "sh"
+extensions = ["sh"]
+shebangs = ["sh", "python"]"#,
+            r#"name = "make"
+filenames = ["Makefile"]
+globs = ["*.mk", "docker/**/Dockerfile"]"#,
+            r#"name = "all"
+sublanguages = ["make", "sh"]"#,
+        ] {
+            let src: LanguageSrc = toml::from_str(i).expect("toml");
+            let lang = Language::from_src(src, &languages).expect("from_src");
+            drop(languages.insert(lang.peek_name().clone(), lang));
+        }
+        let all = languages.get("all").expect("all");
+        let name = |path: &str| {
+            all.check_path(Path::new(path), &languages)
+                .map(|x| x.peek_name().as_str())
+        };
+        assert_eq!(name("a/Makefile"), Some("make"));
+        assert_eq!(name("a/b.mk"), Some("make"));
+        assert_eq!(name("docker/x/Dockerfile"), Some("make"));
+        assert_eq!(name("Dockerfile"), None);
+        assert_eq!(name("a.sh"), Some("sh"));
+        assert_eq!(name("a.txt"), None);
+        let text = |path: &str, text: &str| {
+            all.check_text(Path::new(path), text, &languages)
+                .map(|x| x.peek_name().as_str())
+        };
+        assert_eq!(text("hook", "#!/bin/sh\nexit 0\n"), Some("sh"));
+        assert_eq!(text("Makefile", "#!/bin/sh\n"), Some("make"));
+        assert_eq!(text("hook", "exit 0\n"), None);
+        let sh = languages.get("sh").expect("sh");
+        assert!(sh.check_shebang("python3.12"));
+        assert!(!sh.check_shebang("pythonw"));
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn layer() {
+        let mut sh: LanguageSrc = toml::from_str(
+            r##"name = "sh"
+extensions = ["sh"]
+line_comment_begin = "#""##,
+        )
+        .expect("sh");
+        sh.layer(
+            toml::from_str(
+                r#"name = "sh"
+column = 100"#,
+            )
+            .expect("layer"),
+        );
+        let mut languages = BTreeMap::new();
+        let sh = Language::from_src(sh, &languages).expect("sh");
+        assert_eq!(sh.extensions, ["sh"]);
+        assert_eq!(sh.peek_column(), Some(100));
+        drop(languages.insert(sh.peek_name().clone(), sh));
+        let make: LanguageSrc = toml::from_str(
+            r#"name = "make"
+base = "sh"
+separator_threshold = 4"#,
+        )
+        .expect("make");
+        let make = Language::from_src(make, &languages).expect("make");
+        assert_eq!(make.peek_column(), Some(100));
+        assert_eq!(make.peek_separator_threshold(), Some(4));
+        assert!(make.has_line_comment());
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn invalid_pattern() {
+        for i in [
+            r#"name = "lua"
+line_comment_begin = "--"
+block_comment_begin = "--[["
+block_comment_end = "]]--""#,
+            r##"name = "nim"
+line_comment_begin = "#"
+block_comment_begin = "#["
+block_comment_end = "]#""##,
+        ] {
+            let src: LanguageSrc = toml::from_str(i).expect("toml");
+            assert!(matches!(
+                Language::from_src(src, &BTreeMap::new()),
+                Err(Error::InvalidConfig(_))
+            ));
+        }
+        let lua: LanguageSrc = toml::from_str(
+            r#"name = "lua"
+line_comment_begin = "--"
+block_comment_begin = "--\\[\\["
+block_comment_end = "\\]\\]--""#,
         )
+        .expect("lua");
+        let lua = Language::from_src(lua, &BTreeMap::new()).expect("lua");
+        assert!(lua.re_bcb().is_some_and(|x| x.is_match("--[[ a")));
     }
 }
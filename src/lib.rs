@@ -6,7 +6,7 @@
 //  @author hanepjiv <hanepjiv@gmail.com>
 //  @copyright The MIT License (MIT) / Apache License Version 2.0
 //  @since 2016/10/12
-//  @date 2026/05/30
+//  @date 2026/10/18
 
 // ////////////////////////////////////////////////////////////////////////////
 // attribute  =================================================================
@@ -16,29 +16,49 @@
 extern crate alloc;
 // mod  =======================================================================
 mod ask;
+mod backup;
+mod baseline;
+mod changes;
 mod config;
 mod error;
 mod flags;
 mod inspector;
 mod language;
 mod line_type;
+mod modeline;
+mod options;
+mod reflow;
+mod report;
+mod width;
 // use  =======================================================================
-use std::{fs::File, io::Write as _, path::PathBuf};
+use std::{
+    fs::File,
+    io::Write as _,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+};
 // ----------------------------------------------------------------------------
 use env_logger as _;
 use getopts as _;
+use ignore::{DirEntry, WalkBuilder, WalkState};
 use log::info;
 // ----------------------------------------------------------------------------
+use self::backup::Backup;
+use self::changes::Changes;
 use self::config::Config;
 pub use self::error::Error;
 pub use self::flags::Flags;
-use self::inspector::{Checker, Inspector, Replacer};
+use self::inspector::{Checker, Inspector, Replacer, Target};
+pub use self::options::Options;
+use self::report::Format;
+pub use self::report::Summary;
 // define  ====================================================================
 const CONFIG_DIRNAME: &str = ".config";
 const CONFIG_DEFAULT_PATH: &str = "default.toml";
 const CONFIG_USER_PATH: &str = "user.toml";
 const CONFIG_DEFAULT: &str = include_str!("config/default.toml");
 const CONFIG_USER: &str = include_str!("config/user.toml");
+const IGNORE_FILENAME: &str = ".column79ignore";
 // ////////////////////////////////////////////////////////////////////////////
 // ============================================================================
 /// enum Command.
@@ -74,8 +94,12 @@ impl<'a> From<&'a str> for Command {
 pub struct Column79 {
     /// command
     command: Command,
-    /// input
+    /// input, the origin of the project config and git.
     input: PathBuf,
+    /// inputs, files and directories or `-` for the standard input.
+    inputs: Vec<PathBuf>,
+    /// `stdin_filename`
+    stdin_filename: Option<PathBuf>,
     /// `config_dir`
     config_dir: PathBuf,
     /// `config_default_path`
@@ -108,18 +132,19 @@ impl Column79 {
     // ========================================================================
     /// run
     ///
+    /// Returns the `Summary` of the violations found by `Command::Check`.
+    /// The project configuration is looked up from the first input.
+    ///
     /// # Errors
     ///
     /// `Error::Column79`
     #[inline]
     pub fn run(
         command: Command,
-        input: PathBuf,
-        language: Option<String>,
-        column: Option<usize>,
-        septhr: Option<usize>,
-        flags: Flags,
-    ) -> Result<(), Error> {
+        inputs: Vec<PathBuf>,
+        options: Options,
+    ) -> Result<Summary, Error> {
+        let input = Self::origin(&inputs, options.stdin_filename.as_deref())?;
         // config_dir  --------------------------------------------------------
         let mut config_dir = dirs::home_dir().ok_or_else(|| {
             Error::Column79(format!(
@@ -139,12 +164,32 @@ impl Column79 {
                 ))
             },
         )?);
+        Self::run_in(config_dir, command, inputs, options)
+    }
+    // ------------------------------------------------------------------------
+    /// `run_in`
+    ///
+    /// `run` with the config files in `config_dir`.
+    fn run_in(
+        config_dir: PathBuf,
+        command: Command,
+        inputs: Vec<PathBuf>,
+        options: Options,
+    ) -> Result<Summary, Error> {
+        let input = Self::origin(&inputs, options.stdin_filename.as_deref())?;
         if !config_dir.exists() {
             std::fs::create_dir_all(config_dir.clone())?;
         }
         // config_default_path  -----------------------------------------------
+        // a reference copy of the built-in defaults, never imported, so that
+        // a stale copy left by an older version can not shadow them.
         let config_default_path = config_dir.clone().join(CONFIG_DEFAULT_PATH);
-        if !config_default_path.exists() {
+        if std::fs::read_to_string(&config_default_path)
+            .ok()
+            .as_deref()
+            != Some(CONFIG_DEFAULT)
+        {
+            info!("Column79::run update {:?}", config_default_path.display());
             Self::create_config(&config_default_path, CONFIG_DEFAULT)?;
         }
         // config_user_path  --------------------------------------------------
@@ -153,29 +198,51 @@ impl Column79 {
             Self::create_config(&config_user_path, CONFIG_USER)?;
         }
 
-        let mut config =
-            Config::new(&config_default_path.clone().into_os_string())?;
+        let mut config = Config::new(CONFIG_DEFAULT)?;
         config.import(&config_user_path.clone().into_os_string())?;
+        config.current = std::env::current_dir()?;
+        config.root = config.current.clone();
+        if Command::Init != command {
+            if let Some(x) = config.import_project(&input)? {
+                info!("Column79::run project config {:?}", x.display());
+            }
+        }
 
-        if let Some(x) = column {
-            config.column = x;
+        config.force_column = options.column;
+        config.force_separator_threshold = options.separator_threshold;
+
+        if let Some(x) = options.language {
+            config.language = x;
         }
 
-        if let Some(x) = septhr {
-            config.separator_threshold = x;
+        if let Some(x) = options.format {
+            config.format = Format::from(x.as_str());
         }
 
-        if let Some(x) = language {
-            config.language = x;
+        if let Some(x) = options.backup {
+            config.backup = Backup::from(x.as_str());
         }
 
-        config.flags.insert(flags);
+        config.include.extend(options.include);
+        config.exclude.extend(options.exclude);
+
+        config.baseline = options.baseline;
+        config.write_baseline = options.write_baseline;
+        config.changed_since = options.changed_since;
+
+        if let Some(x) = options.jobs {
+            config.jobs = x;
+        }
+
+        config.flags.insert(options.flags);
 
         config.validation()?;
 
         let c79 = Self {
             command,
             input,
+            inputs,
+            stdin_filename: options.stdin_filename,
             config_dir,
             config_default_path,
             config_user_path,
@@ -187,36 +254,170 @@ impl Column79 {
                  invalid command {:?}",
                 c79.command
             ))),
-            Command::Init => c79.init(),
+            Command::Init => c79.init().map(|()| Summary::default()),
             Command::Check => c79.check(),
-            Command::Replace => c79.replace(),
+            Command::Replace => c79.replace().map(|()| Summary::default()),
         }
     }
+    // ------------------------------------------------------------------------
+    /// origin
+    ///
+    /// The first input other than `-`, or the existing `stdin_filename`, or
+    /// the current directory.
+    fn origin(
+        inputs: &[PathBuf],
+        stdin_filename: Option<&Path>,
+    ) -> Result<PathBuf, Error> {
+        if let Some(x) = inputs.iter().find(|x| !Self::is_stdin(x)) {
+            return Ok(x.clone());
+        }
+        if let Some(x) = stdin_filename.filter(|x| x.exists()) {
+            return Ok(x.to_path_buf());
+        }
+        Ok(std::env::current_dir()?)
+    }
+    // ------------------------------------------------------------------------
+    /// `is_stdin`
+    fn is_stdin(path: &Path) -> bool {
+        path.as_os_str() == "-"
+    }
     // ========================================================================
+    /// files
+    ///
+    /// The files of all inputs but `-`, sorted by path relative to the
+    /// project root, without the duplicates spelled differently such as
+    /// `./src/a.rs` and `src/a.rs`.
+    fn files(&self) -> Result<Vec<Target<'_>>, Error> {
+        let mut ret = Vec::new();
+        for i in self.inputs.iter().filter(|x| !Self::is_stdin(x)) {
+            ret.extend(
+                self.walk(i)?
+                    .into_iter()
+                    .map(|x| (self.config.relative(&x.path), x)),
+            );
+        }
+        ret.sort_by(|a, b| a.0.cmp(&b.0));
+        ret.dedup_by(|a, b| a.0 == b.0);
+        Ok(ret.into_iter().map(|(_, x)| x).collect())
+    }
+    // ------------------------------------------------------------------------
     /// walk
-    fn walk(
-        &self,
-        path: &PathBuf,
-        inspector: &impl Inspector,
-    ) -> Result<(), Error> {
-        for i in std::fs::read_dir(path)? {
-            let entry = i?;
-            let ftype = entry.file_type()?;
-            if ftype.is_dir() {
-                self.walk(&entry.path(), inspector)?;
-                continue;
+    ///
+    /// Honors `.gitignore`, `.ignore` and `.column79ignore` unless
+    /// `Flags::NOIGNORE` is set, and selects files, a file given as `path`
+    /// itself too, by the `include` and `exclude` globs relative to the
+    /// project root.  The `overrides` matching a file may skip it or set its
+    /// language.  Walks on `jobs` threads.
+    fn walk(&self, path: &PathBuf) -> Result<Vec<Target<'_>>, Error> {
+        let mut builder = WalkBuilder::new(path);
+        let no_ignore = self.config.flags.contains(Flags::NOIGNORE);
+        if no_ignore {
+            let _ = builder.standard_filters(false);
+        } else {
+            let _ = builder
+                .hidden(false)
+                .require_git(false)
+                .add_custom_ignore_filename(IGNORE_FILENAME);
+        }
+        {
+            let exclude = self.config.peek_exclude_set().clone();
+            let root = self.config.root.clone();
+            let current = self.config.current.clone();
+            let backup_dir = (Backup::Directory == self.config.backup)
+                .then(|| {
+                    self.config
+                        .root
+                        .join(&self.config.backup_dir)
+                        .canonicalize()
+                        .ok()
+                })
+                .flatten();
+            let _ = builder.filter_entry(move |entry| {
+                if 0 == entry.depth() {
+                    return true;
+                }
+                if !no_ignore && entry.file_name() == ".git" {
+                    return false;
+                }
+                if let Some(ref x) = backup_dir
+                    && entry.file_type().is_some_and(|t| t.is_dir())
+                    && entry.path().canonicalize().is_ok_and(|p| p == *x)
+                {
+                    return false;
+                }
+                !exclude.is_match(Config::relative_to(
+                    &root,
+                    &current,
+                    entry.path(),
+                ))
+            });
+        }
+        let jobs = self.config.jobs();
+        let mut ret = Vec::new();
+        if 1 < jobs {
+            let found = Mutex::new((Vec::new(), None));
+            builder.threads(jobs).build_parallel().run(|| {
+                Box::new(|i| {
+                    let lock = || {
+                        found.lock().unwrap_or_else(PoisonError::into_inner)
+                    };
+                    match i {
+                        Ok(entry) => {
+                            if let Some(x) = self.select(&entry) {
+                                lock().0.push(x);
+                            }
+                            WalkState::Continue
+                        }
+                        Err(e) => {
+                            let _ = lock().1.get_or_insert(Error::from(e));
+                            WalkState::Quit
+                        }
+                    }
+                })
+            });
+            let (files, error) =
+                found.into_inner().unwrap_or_else(PoisonError::into_inner);
+            if let Some(e) = error {
+                return Err(e);
             }
-            let entry_path = &entry.path();
-            if let Some(language) = self.config.check_path(entry_path) {
-                info!(
-                    "Column79::walk {} {:?}",
-                    language.peek_name(),
-                    entry_path.display()
-                );
-                inspector.inspect(language, entry_path)?;
+            ret = files;
+        } else {
+            for i in builder.build() {
+                ret.extend(self.select(&i?));
             }
         }
-        Ok(())
+        Ok(ret)
+    }
+    // ------------------------------------------------------------------------
+    /// select
+    fn select(&self, entry: &DirEntry) -> Option<Target<'_>> {
+        if entry.file_type().is_none_or(|ftype| ftype.is_dir()) {
+            return None;
+        }
+        let entry_path = entry.path().to_path_buf();
+        let relative = self.config.relative(&entry_path);
+        if !self.config.is_included(&relative) {
+            return None;
+        }
+        let over = self.config.check_override(&relative);
+        if over.is_skip() {
+            info!("Column79::walk skip {:?}", entry_path.display());
+            return None;
+        }
+        let language = match over.language {
+            Some(ref x) => self.config.languages.get(x),
+            None => self.config.check_path(&entry_path),
+        }?;
+        info!(
+            "Column79::walk {} {:?}",
+            language.peek_name(),
+            entry_path.display()
+        );
+        Some(Target {
+            language,
+            over,
+            path: entry_path,
+        })
     }
     // ========================================================================
     /// init
@@ -235,12 +436,243 @@ impl Column79 {
     }
     // ========================================================================
     /// check
-    fn check(&self) -> Result<(), Error> {
-        self.walk(&self.input, &Checker::new(&self.config))
+    fn check(&self) -> Result<Summary, Error> {
+        let changes = Changes::new(&self.config, &self.input)?;
+        let checker = Checker::new(&self.config, changes)?;
+        checker.inspect_all(&self.files()?, self.config.jobs())?;
+        if self.inputs.iter().any(|x| Self::is_stdin(x)) {
+            self.check_stdin(&checker)?;
+        }
+        checker.finish()
+    }
+    // ------------------------------------------------------------------------
+    /// `check_stdin`
+    ///
+    /// Checks the standard input as `stdin_filename`, in the language of
+    /// that name or of the shebang line of the input, or in `language` if
+    /// not named.
+    fn check_stdin(&self, checker: &Checker<'_>) -> Result<(), Error> {
+        let path = self
+            .stdin_filename
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("-"));
+        let relative = self.config.relative(&path);
+        if self.stdin_filename.is_some() && !self.config.is_included(&relative)
+        {
+            info!("Column79::check_stdin skip {:?}", path.display());
+            return Ok(());
+        }
+        let over = self.config.check_override(&relative);
+        if over.is_skip() {
+            info!("Column79::check_stdin skip {:?}", path.display());
+            return Ok(());
+        }
+        let text = std::io::read_to_string(std::io::stdin())?;
+        let language =
+            match (over.language.as_ref(), self.stdin_filename.as_ref()) {
+                (Some(x), _) => self.config.languages.get(x),
+                (None, Some(x)) => self.config.check_text(x, &text),
+                (None, None) => {
+                    self.config.languages.get(&self.config.language)
+                }
+            }
+            .ok_or_else(|| {
+                Error::Column79(format!(
+                    "::column79::lib::Column79::check_stdin: \
+                     no language for the standard input \"{0}\"",
+                    path.display()
+                ))
+            })?;
+        checker.inspect_text(
+            &Target {
+                language,
+                over,
+                path,
+            },
+            &text,
+        )
     }
     // ========================================================================
     /// replace
     fn replace(&self) -> Result<(), Error> {
-        self.walk(&self.input, &Replacer::new(&self.config))
+        if self.inputs.iter().any(|x| Self::is_stdin(x)) {
+            return Err(Error::Column79(
+                "::column79::lib::Column79::replace: \
+                 the standard input is only supported by check"
+                    .to_owned(),
+            ));
+        }
+        let replacer = Replacer::new(&self.config);
+        for i in self.files()? {
+            replacer.inspect(&i)?;
+        }
+        Ok(())
+    }
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    // use  ===================================================================
+    use std::path::{Path, PathBuf};
+    // ------------------------------------------------------------------------
+    use super::{
+        CONFIG_DEFAULT, Column79, Command, Error, Flags, Options,
+        config::Config,
+    };
+    // ========================================================================
+    fn files(root: &Path, inputs: &[PathBuf], flags: Flags) -> Vec<PathBuf> {
+        let input = inputs.first().expect("input");
+        let mut config = Config::new(CONFIG_DEFAULT).expect("Config::new");
+        config.current.clone_from(input);
+        config.flags.insert(flags);
+        assert!(
+            config
+                .import_project(input)
+                .expect("import_project")
+                .is_some()
+        );
+        config.validation().expect("validation");
+        let column79 = Column79 {
+            command: Command::Check,
+            input: input.clone(),
+            inputs: inputs.to_vec(),
+            stdin_filename: None,
+            config_dir: PathBuf::new(),
+            config_default_path: PathBuf::new(),
+            config_user_path: PathBuf::new(),
+            config,
+        };
+        column79
+            .files()
+            .expect("files")
+            .into_iter()
+            .map(|x| x.path.strip_prefix(root).expect("root").to_path_buf())
+            .collect()
+    }
+    // ========================================================================
+    #[test]
+    fn walk_subdirectory() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path().canonicalize().expect("canonicalize");
+        std::fs::create_dir_all(root.join("src/gen")).expect("gen");
+        std::fs::create_dir_all(root.join("src/generated"))
+            .expect("generated");
+        std::fs::write(
+            root.join(".column79.toml"),
+            "exclude = [\"**/generated/**\"]\n\
+             [[overrides]]\n\
+             globs = [\"src/gen/**\"]\n\
+             skip = true\n",
+        )
+        .expect("write");
+        for i in ["src/a.rs", "src/gen/g.rs", "src/generated/b.rs"] {
+            std::fs::write(root.join(i), "fn f() {}\n").expect("write");
+        }
+        for input in [root.clone(), root.join("src")] {
+            assert_eq!(
+                files(&root, &[input], Flags::empty()),
+                [Path::new("src/a.rs")]
+            );
+        }
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn walk_ignore() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path().canonicalize().expect("canonicalize");
+        std::fs::create_dir_all(root.join("src")).expect("src");
+        for (name, text) in [
+            (".column79.toml", ""),
+            (".gitignore", "/src/a.rs\n"),
+            (".ignore", "b.rs\n"),
+            ("src/.column79ignore", "c.rs\n"),
+        ] {
+            std::fs::write(root.join(name), text).expect("write");
+        }
+        for i in ["src/a.rs", "src/b.rs", "src/c.rs", "src/d.rs"] {
+            std::fs::write(root.join(i), "fn f() {}\n").expect("write");
+        }
+        let inputs = [root.clone()];
+        assert_eq!(
+            files(&root, &inputs, Flags::empty()),
+            [Path::new("src/d.rs")]
+        );
+        assert_eq!(
+            files(&root, &inputs, Flags::NOIGNORE),
+            ["src/a.rs", "src/b.rs", "src/c.rs", "src/d.rs"].map(Path::new)
+        );
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn files_dedup() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path().canonicalize().expect("canonicalize");
+        std::fs::create_dir_all(root.join("src")).expect("src");
+        std::fs::write(root.join(".column79.toml"), "").expect("write");
+        for i in ["src/a.rs", "src/b.rs"] {
+            std::fs::write(root.join(i), "fn f() {}\n").expect("write");
+        }
+        assert_eq!(
+            files(
+                &root,
+                &[
+                    root.clone(),
+                    root.join("src/../src"),
+                    root.join("src/../src/a.rs"),
+                    root.join("src/b.rs"),
+                ],
+                Flags::empty()
+            ),
+            [Path::new("src/a.rs"), Path::new("src/b.rs")]
+        );
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn run() {
+        let config_dir = tempfile::tempdir().expect("tempdir");
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path().canonicalize().expect("canonicalize");
+        std::fs::create_dir_all(root.join("src")).expect("src");
+        std::fs::write(root.join(".column79.toml"), "").expect("write");
+        let long = format!("// {}\n", "x".repeat(80));
+        std::fs::write(root.join("src/a.rs"), long.repeat(2)).expect("write");
+        std::fs::write(root.join("src/b.rs"), "fn f() {}\n").expect("write");
+        let run = |command, options| {
+            Column79::run_in(
+                config_dir.path().join("column79"),
+                command,
+                vec![root.join("src")],
+                options,
+            )
+        };
+        let summary = run(Command::Check, Options::default()).expect("run");
+        assert_eq!((summary.files(), summary.violations()), (2, 2));
+        let summary = run(
+            Command::Check,
+            Options {
+                exclude: vec!["src/a.rs".to_owned()],
+                ..Options::default()
+            },
+        )
+        .expect("run");
+        assert_eq!((summary.files(), summary.violations()), (1, 0));
+        assert!(matches!(
+            run(
+                Command::Check,
+                Options {
+                    backup: Some("bogus".to_owned()),
+                    ..Options::default()
+                },
+            ),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            run(Command::Unknown, Options::default()),
+            Err(Error::Column79(_))
+        ));
+        std::fs::write(root.join(".column79.toml"), "column = \"x\"\n")
+            .expect("write");
+        assert!(run(Command::Check, Options::default()).is_err());
     }
 }
@@ -6,17 +6,18 @@
 //  @author hanepjiv <hanepjiv@gmail.com>
 //  @copyright The MIT License (MIT) / Apache License Version 2.0
 //  @since 2016/10/21
-//  @date 2026/05/30
+//  @date 2026/10/18
 
 // ////////////////////////////////////////////////////////////////////////////
 // use  =======================================================================
+use regex::Regex;
 use unicode_segmentation::UnicodeSegmentation as _;
 // ----------------------------------------------------------------------------
-use crate::{config::Config, language::Language};
+use crate::{config::Limits, language::Language};
 // ////////////////////////////////////////////////////////////////////////////
 // ============================================================================
 /// enum `LineType`.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum LineType {
     /// `LineComment`
     LineComment(String, String),
@@ -26,6 +27,10 @@ pub(crate) enum LineType {
     BlockComment(String, String, String),
     /// `BlockSeparator`
     BlockSeparator(String, String, String),
+    /// `BlockInner`, a line of a block comment spanning multiple lines.
+    BlockInner(String, String, String),
+    /// `Ignored`, a line suppressed by a `column79:` pragma.
+    Ignored,
     /// Other
     Other,
 }
@@ -37,9 +42,10 @@ impl LineType {
             Self::LineComment(ref head, _)
             | Self::LineSeparator(ref head, _)
             | Self::BlockComment(ref head, _, _)
-            | Self::BlockSeparator(ref head, _, _) => Some(head),
+            | Self::BlockSeparator(ref head, _, _)
+            | Self::BlockInner(ref head, _, _) => Some(head),
 
-            Self::Other => None,
+            Self::Ignored | Self::Other => None,
         }
     }
     // ------------------------------------------------------------------------
@@ -48,26 +54,41 @@ impl LineType {
             Self::LineComment(_, ref body)
             | Self::LineSeparator(_, ref body)
             | Self::BlockComment(_, ref body, _)
-            | Self::BlockSeparator(_, ref body, _) => Some(body),
+            | Self::BlockSeparator(_, ref body, _)
+            | Self::BlockInner(_, ref body, _) => Some(body),
 
-            Self::Other => None,
+            Self::Ignored | Self::Other => None,
         }
     }
     // ------------------------------------------------------------------------
     pub(crate) const fn foot(&self) -> Option<&String> {
         match *self {
             Self::BlockComment(_, _, ref foot)
-            | Self::BlockSeparator(_, _, ref foot) => Some(foot),
+            | Self::BlockSeparator(_, _, ref foot)
+            | Self::BlockInner(_, _, ref foot) => Some(foot),
 
             Self::LineComment(_, _)
             | Self::LineSeparator(_, _)
+            | Self::Ignored
             | Self::Other => None,
         }
     }
+    // ------------------------------------------------------------------------
+    pub(crate) const fn name(&self) -> &'static str {
+        match *self {
+            Self::LineComment(_, _) => "line_comment",
+            Self::LineSeparator(_, _) => "line_separator",
+            Self::BlockComment(_, _, _) => "block_comment",
+            Self::BlockSeparator(_, _, _) => "block_separator",
+            Self::BlockInner(_, _, _) => "block_inner",
+            Self::Ignored => "ignored",
+            Self::Other => "other",
+        }
+    }
     // ========================================================================
     #[expect(clippy::unwrap_used, reason = "checked")]
-    pub(crate) fn is_separator(conf: &Config, body: &str) -> bool {
-        let t = conf.separator_threshold;
+    pub(crate) fn is_separator(limits: Limits, body: &str) -> bool {
+        let t = limits.separator_threshold;
         if body.graphemes(true).count() < t {
             return false;
         }
@@ -84,19 +105,23 @@ impl LineType {
         true
     }
     // ========================================================================
+    /// `is_line_comment`
+    ///
+    /// `masked` is `line` with its string literals masked out.
     #[expect(clippy::unwrap_used, reason = "checked")]
     pub(crate) fn is_line_comment(
-        conf: &Config,
+        limits: Limits,
         lang: &Language,
         line: &str,
+        masked: &str,
     ) -> Option<Self> {
         if !lang.has_line_comment() {
             return None;
         }
-        lang.re_line_captures(line).map(|c| -> Self {
-            let head = String::from(c.get(1).unwrap().as_str());
-            let body = String::from(c.get(2).unwrap().as_str());
-            if Self::is_separator(conf, &body) {
+        lang.re_line_captures(masked).map(|c| -> Self {
+            let head = String::from(&line[c.get(1).unwrap().range()]);
+            let body = String::from(&line[c.get(2).unwrap().range()]);
+            if Self::is_separator(limits, &body) {
                 Self::LineSeparator(head, body)
             } else {
                 Self::LineComment(head, body)
@@ -104,30 +129,393 @@ impl LineType {
         })
     }
     // ------------------------------------------------------------------------
+    /// `is_block_comment`
+    ///
+    /// `masked` is `line` with its string literals masked out.
     #[expect(clippy::unwrap_used, reason = "checked")]
     pub(crate) fn is_block_comment(
-        conf: &Config,
+        limits: Limits,
         lang: &Language,
         line: &str,
+        masked: &str,
     ) -> Option<Self> {
         if !lang.has_block_comment() {
             return None;
         }
-        lang.re_block_captures(line).map(|c| -> Self {
-            let head = String::from(c.get(1).unwrap().as_str());
-            let body = String::from(c.get(2).unwrap().as_str());
-            let foot = String::from(c.get(3).unwrap().as_str());
-            if Self::is_separator(conf, &body) {
+        lang.re_block_captures(masked).map(|c| -> Self {
+            let head = String::from(&line[c.get(1).unwrap().range()]);
+            let body = String::from(&line[c.get(2).unwrap().range()]);
+            let foot = String::from(&line[c.get(3).unwrap().range()]);
+            if Self::is_separator(limits, &body) {
                 Self::BlockSeparator(head, body, foot)
             } else {
                 Self::BlockComment(head, body, foot)
             }
         })
     }
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+/// struct `LineClassifier`.
+///
+/// Classifies lines in order, tracking whether a block comment or a string
+/// literal opened on a previous line is still open.
+///
+/// A comment containing `column79: ignore-line`, `ignore-next-line`,
+/// `ignore-start` or `ignore-end` is a pragma; the pragma line and the
+/// lines it suppresses are `LineType::Ignored`.
+#[derive(Debug)]
+pub(crate) struct LineClassifier<'a> {
+    /// limits
+    limits: Limits,
+    /// lang
+    lang: &'a Language,
+    /// `re_gutter`
+    re_gutter: Regex,
+    /// `re_pragma`
+    re_pragma: Regex,
+    /// block
+    block: bool,
+    /// literal, the length of the text after a string literal still open.
+    literal: Option<usize>,
+    /// `ignore_next`
+    ignore_next: bool,
+    /// `ignore_region`
+    ignore_region: bool,
+}
+// ============================================================================
+impl<'a> LineClassifier<'a> {
     // ========================================================================
-    pub(crate) fn new(conf: &Config, lang: &Language, line: &str) -> Self {
-        Self::is_block_comment(conf, lang, line).unwrap_or_else(|| {
-            Self::is_line_comment(conf, lang, line).unwrap_or(Self::Other)
-        })
+    #[expect(clippy::expect_used, reason = "checked")]
+    pub(crate) fn new(limits: Limits, lang: &'a Language) -> Self {
+        Self {
+            limits,
+            lang,
+            re_gutter: Regex::new(r"^\s*(?:\*(?:\s+|$))?")
+                .expect("LineClassifier::new"),
+            re_pragma: Regex::new(
+                r"column79:\s*ignore-(line|next-line|start|end)\b",
+            )
+            .expect("LineClassifier::new"),
+            block: false,
+            literal: None,
+            ignore_next: false,
+            ignore_region: false,
+        }
+    }
+    // ========================================================================
+    /// classify
+    ///
+    /// `text` is `line` followed by the rest of the text.
+    pub(crate) fn classify(&mut self, line: &str, text: &str) -> LineType {
+        let line_type = self.classify_comment(line, text);
+        let ignored =
+            core::mem::take(&mut self.ignore_next) || self.ignore_region;
+        let pragma = line_type
+            .body()
+            .and_then(|x| self.re_pragma.captures(x))
+            .and_then(|x| x.get(1));
+        match pragma.map(|x| x.as_str()) {
+            Some("next-line") => self.ignore_next = true,
+            Some("start") => self.ignore_region = true,
+            Some("end") => self.ignore_region = false,
+            Some(_) => {}
+            None if !ignored => return line_type,
+            None => {}
+        }
+        LineType::Ignored
+    }
+    // ------------------------------------------------------------------------
+    /// `classify_comment`
+    fn classify_comment(&mut self, line: &str, text: &str) -> LineType {
+        if self.block {
+            return self.inner(line);
+        }
+        let start = match self.literal.take() {
+            Some(tail) => {
+                let end = text.len().saturating_sub(tail);
+                if line.len() < end {
+                    self.literal = Some(tail);
+                    return LineType::Other;
+                }
+                end
+            }
+            None => 0,
+        };
+        let (masked, literal) = self.lang.mask_literals(line, text, start);
+        self.literal = literal;
+        if let Some(x) =
+            LineType::is_block_comment(self.limits, self.lang, line, &masked)
+        {
+            return x;
+        }
+        if let Some(x) = self.begin(line, &masked) {
+            return x;
+        }
+        LineType::is_line_comment(self.limits, self.lang, line, &masked)
+            .unwrap_or(LineType::Other)
+    }
+    // ------------------------------------------------------------------------
+    /// `is_open`
+    ///
+    /// Whether a block comment is still open at the end of `line` from
+    /// `start`.
+    fn is_open(&self, line: &str, start: usize, mut block: bool) -> bool {
+        let (Some(bcb), Some(bce)) = (self.lang.re_bcb(), self.lang.re_bce())
+        else {
+            return false;
+        };
+        let mut pos = start;
+        loop {
+            let found = if block {
+                bce.find_at(line, pos)
+            } else {
+                bcb.find_at(line, pos)
+            };
+            match found {
+                Some(m) if m.end() > pos => {
+                    pos = m.end();
+                    block = !block;
+                }
+                Some(_) | None => return block,
+            }
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// begin
+    ///
+    /// A line opening a block comment after code or another comment is
+    /// `LineType::Other`, the block still opened.
+    fn begin(&mut self, line: &str, masked: &str) -> Option<LineType> {
+        if !self.lang.has_block_comment() {
+            return None;
+        }
+        let m = self.lang.re_bcb()?.find(masked)?;
+        if let Some(lcb) = self.lang.re_lcb() {
+            if lcb.find(masked).is_some_and(|x| x.start() < m.start()) {
+                return None;
+            }
+        }
+        if !self.is_open(masked, m.end(), true) {
+            return None;
+        }
+        self.block = true;
+        if !masked[..m.start()].trim().is_empty()
+            || self.lang.re_bce()?.is_match(&masked[m.end()..])
+        {
+            return Some(LineType::Other);
+        }
+        let rest = &line[m.end()..];
+        let body = rest.trim_start();
+        let head = &line[..line.len() - body.len()];
+        Some(LineType::BlockInner(
+            head.to_owned(),
+            body.to_owned(),
+            String::new(),
+        ))
+    }
+    // ------------------------------------------------------------------------
+    /// inner
+    fn inner(&mut self, line: &str) -> LineType {
+        let end = self.lang.re_bce().and_then(|bce| bce.find(line));
+        let (content, foot) = end.map_or((line, ""), |m| {
+            self.block = self.is_open(line, m.end(), false);
+            let content = line[..m.start()].trim_end();
+            (content, &line[content.len()..])
+        });
+        let head_len = self.re_gutter.find(content).map_or(0, |x| x.end());
+        LineType::BlockInner(
+            content[..head_len].to_owned(),
+            content[head_len..].to_owned(),
+            foot.to_owned(),
+        )
+    }
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    // use  ===================================================================
+    use alloc::collections::BTreeMap;
+    // ------------------------------------------------------------------------
+    use super::{LineClassifier, LineType};
+    use crate::{
+        config::Limits,
+        language::{Language, LanguageSrc},
+    };
+    // ========================================================================
+    fn c() -> Language {
+        let src: LanguageSrc = toml::from_str(
+            r#"
+            name = "c"
+            line_comment_begin = "//"
+            block_comment_begin = "/\\*"
+            block_comment_end = "\\*/"
+            string_literals = ['"(?:[^"\\\\]|\\\\.)*"']
+            "#,
+        )
+        .expect("c");
+        Language::from_src(src, &BTreeMap::new()).expect("c")
+    }
+    // ------------------------------------------------------------------------
+    fn haskell() -> Language {
+        let src: LanguageSrc = toml::from_str(
+            r#"
+            name = "haskell"
+            line_comment_begin = "--"
+            block_comment_begin = "\\{-"
+            block_comment_end = "-\\}"
+            string_literals = ['"(?:[^"\\\\]|\\\\.)*"']
+            "#,
+        )
+        .expect("haskell");
+        Language::from_src(src, &BTreeMap::new()).expect("haskell")
+    }
+    // ------------------------------------------------------------------------
+    fn limits() -> Limits {
+        Limits {
+            column: 79,
+            separator_threshold: 12,
+        }
+    }
+    // ------------------------------------------------------------------------
+    fn classify(lang: &Language, lines: &[&str]) -> Vec<LineType> {
+        let mut classifier = LineClassifier::new(limits(), lang);
+        let text = lines.join("\n");
+        let mut pos = 0;
+        lines
+            .iter()
+            .map(|x| {
+                let ret = classifier.classify(x, &text[pos..]);
+                pos += x.len() + 1;
+                ret
+            })
+            .collect()
+    }
+    // ========================================================================
+    #[test]
+    fn pragma() {
+        let ignored = classify(
+            &c(),
+            &[
+                "a(); // column79: ignore-line",
+                "/* column79: ignore-next-line */",
+                "b();",
+                "c();",
+                "/*",
+                " * column79: ignore-start",
+                " */",
+                "d();",
+                "// column79:ignore-end",
+                "\"// column79: ignore-line\";",
+                "e(); /* column79: ignore-lines */",
+            ],
+        )
+        .iter()
+        .map(|x| matches!(x, LineType::Ignored))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            ignored,
+            [
+                true, true, true, false, false, true, true, true, true, false,
+                false
+            ]
+        );
+    }
+    // ------------------------------------------------------------------------
+    fn inner(head: &str, body: &str, foot: &str) -> LineType {
+        LineType::BlockInner(head.to_owned(), body.to_owned(), foot.to_owned())
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn block() {
+        assert_eq!(
+            classify(&c(), &["/*", " * a", " b", " */ code", "x();"]),
+            [
+                inner("/*", "", ""),
+                inner(" * ", "a", ""),
+                inner(" ", "b", ""),
+                inner("", "", " */ code"),
+                LineType::Other,
+            ]
+        );
+        assert_eq!(
+            classify(&haskell(), &["{-", " | a", " -}", "x"]),
+            [
+                inner("{-", "", ""),
+                inner(" ", "| a", ""),
+                inner("", "", " -}"),
+                LineType::Other,
+            ]
+        );
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn block_open() {
+        assert_eq!(
+            classify(&c(), &["/* a */ code /* open", " * b", " */", "x();"]),
+            [
+                LineType::Other,
+                inner(" * ", "b", ""),
+                inner("", "", " */"),
+                LineType::Other,
+            ]
+        );
+        assert_eq!(
+            classify(&c(), &["// a /*", "x();"]),
+            [
+                LineType::LineComment("// ".to_owned(), "a /*".to_owned()),
+                LineType::Other,
+            ]
+        );
+        assert_eq!(
+            classify(&c(), &["s = \"/*\";", "x();"]),
+            [LineType::Other, LineType::Other]
+        );
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn literal() {
+        let python = Language::from_src(
+            toml::from_str(
+                r##"
+                name = "python"
+                line_comment_begin = "#"
+                string_literals = ['"""(?s:.*?)"""', '"(?:[^"\\\\]|\\\\.)*"']
+                "##,
+            )
+            .expect("python"),
+            &BTreeMap::new(),
+        )
+        .expect("python");
+        let lines = [
+            "def f():",
+            "    \"\"\"",
+            "    # docstring text aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "    \"\"\"  # a",
+            "    # b",
+        ];
+        assert_eq!(
+            classify(&python, &lines),
+            [
+                LineType::Other,
+                LineType::Other,
+                LineType::Other,
+                LineType::LineComment(
+                    "    \"\"\"  # ".to_owned(),
+                    "a".to_owned()
+                ),
+                LineType::LineComment("    # ".to_owned(), "b".to_owned()),
+            ]
+        );
+        assert_eq!(
+            classify(&c(), &["s = \"a", "// b /*", "\";", "// c"]),
+            [
+                LineType::Other,
+                LineType::Other,
+                LineType::Other,
+                LineType::LineComment("// ".to_owned(), "c".to_owned()),
+            ]
+        );
     }
 }
@@ -0,0 +1,188 @@
+// -*- coding:utf-8-unix; -*-
+
+//! modeline.rs
+
+//  Copyright 2026 hanepjiv
+//  @author hanepjiv <hanepjiv@gmail.com>
+//  @copyright The MIT License (MIT) / Apache License Version 2.0
+//  @since 2026/10/18
+//  @date 2026/10/18
+
+// ////////////////////////////////////////////////////////////////////////////
+// define  ====================================================================
+/// The number of lines at the head and at the tail searched for modelines.
+const LINES: usize = 5;
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+/// struct Modeline.
+///
+/// The settings of an Emacs `-*- mode: rust; fill-column: 79 -*-` or a Vim
+/// `vim: set ft=rust tw=79:` modeline.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Modeline {
+    /// mode, the Emacs mode or the Vim filetype.
+    pub mode: Option<String>,
+    /// column, `fill-column` or `textwidth`.
+    pub column: Option<usize>,
+}
+// ============================================================================
+impl Modeline {
+    // ========================================================================
+    /// parse
+    ///
+    /// Merges the modelines of the first and last `LINES` lines of `text`,
+    /// the first setting found winning.
+    pub(crate) fn parse(text: &str) -> Self {
+        let mut ret = Self::default();
+        for line in text
+            .lines()
+            .take(LINES)
+            .chain(text.lines().rev().take(LINES))
+        {
+            if let Some(x) = Self::emacs(line).or_else(|| Self::vim(line)) {
+                ret.mode = ret.mode.or(x.mode);
+                ret.column = ret.column.or(x.column);
+            }
+        }
+        ret
+    }
+    // ------------------------------------------------------------------------
+    /// `is_modeline`
+    pub(crate) fn is_modeline(line: &str) -> bool {
+        Self::emacs(line).or_else(|| Self::vim(line)).is_some()
+    }
+    // ------------------------------------------------------------------------
+    /// emacs
+    ///
+    /// `-*- mode -*-` or `-*- var: value; ... -*-`.
+    fn emacs(line: &str) -> Option<Self> {
+        let (_, rest) = line.split_once("-*-")?;
+        let (vars, _) = rest.split_once("-*-")?;
+        let mut ret = Self::default();
+        if !vars.contains(':') {
+            ret.mode = Self::mode(vars);
+            return Some(ret);
+        }
+        for i in vars.split(';') {
+            let Some((k, v)) = i.split_once(':') else {
+                continue;
+            };
+            match k.trim().to_lowercase().as_str() {
+                "mode" => ret.mode = Self::mode(v),
+                "fill-column" => ret.column = Self::column(v),
+                _ => {}
+            }
+        }
+        Some(ret)
+    }
+    // ------------------------------------------------------------------------
+    /// vim
+    ///
+    /// `vim: set opt=val ...:` or `vim: opt=val:opt=val`, also as `vi:`,
+    /// `Vim:` or `ex:` at the start of the line or after a white space.
+    fn vim(line: &str) -> Option<Self> {
+        let rest =
+            ["vim:", "Vim:", "vi:", "ex:"].into_iter().find_map(|m| {
+                line.match_indices(m)
+                    .find(|&(i, _)| {
+                        line[..i]
+                            .chars()
+                            .next_back()
+                            .is_none_or(char::is_whitespace)
+                    })
+                    .map(|(i, _)| &line[i + m.len()..])
+            })?;
+        let rest = rest.trim_start();
+        let opts = match rest
+            .strip_prefix("set ")
+            .or_else(|| rest.strip_prefix("se "))
+        {
+            Some(x) => x.split_once(':').map_or(x, |(x, _)| x),
+            None => rest,
+        };
+        let mut ret = Self::default();
+        for i in opts.split(|c: char| c.is_whitespace() || ':' == c) {
+            let Some((k, v)) = i.split_once('=') else {
+                continue;
+            };
+            match k {
+                "ft" | "filetype" => ret.mode = Self::mode(v),
+                "tw" | "textwidth" => ret.column = Self::column(v),
+                _ => {}
+            }
+        }
+        Some(ret)
+    }
+    // ------------------------------------------------------------------------
+    /// mode
+    fn mode(src: &str) -> Option<String> {
+        let ret = src.trim().to_lowercase();
+        let ret = ret.strip_suffix("-mode").unwrap_or(&ret);
+        (!ret.is_empty()).then(|| ret.to_owned())
+    }
+    // ------------------------------------------------------------------------
+    /// column, where `0` is no limit.
+    fn column(src: &str) -> Option<usize> {
+        src.trim().parse().ok().filter(|&x| 0 < x)
+    }
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    // use  ===================================================================
+    use super::Modeline;
+    // ========================================================================
+    fn modeline(mode: Option<&str>, column: Option<usize>) -> Modeline {
+        Modeline {
+            mode: mode.map(ToOwned::to_owned),
+            column,
+        }
+    }
+    // ========================================================================
+    #[test]
+    fn emacs() {
+        assert_eq!(
+            Modeline::parse("// -*- coding:utf-8-unix; -*-\n"),
+            modeline(None, None)
+        );
+        assert_eq!(
+            Modeline::parse("# -*- mode:toml;coding:utf-8; -*-\n"),
+            modeline(Some("toml"), None)
+        );
+        assert_eq!(
+            Modeline::parse("#!/bin/sh\n# -*- Shell-Script -*-\n"),
+            modeline(Some("shell-script"), None)
+        );
+        assert_eq!(
+            Modeline::parse(";; -*- mode: emacs-lisp; fill-column: 70 -*-"),
+            modeline(Some("emacs-lisp"), Some(70))
+        );
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn vim() {
+        assert_eq!(
+            Modeline::parse("x\n/* vim: set ft=cpp tw=100: */\n"),
+            modeline(Some("cpp"), Some(100))
+        );
+        assert_eq!(
+            Modeline::parse("# vi:tw=72:ft=sh\n"),
+            modeline(Some("sh"), Some(72))
+        );
+        assert_eq!(Modeline::parse("regex: ft=sh\n"), modeline(None, None));
+        assert_eq!(Modeline::parse("// vim: tw=0\n"), modeline(None, None));
+        assert!(Modeline::is_modeline("# vim: tw=0"));
+        assert!(!Modeline::is_modeline("# regex: tw=0"));
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn lines() {
+        let mut text = "// vim: tw=60\n".to_owned();
+        text.push_str(&"x\n".repeat(10));
+        text.push_str("// vim: ft=c tw=90\n");
+        assert_eq!(Modeline::parse(&text), modeline(Some("c"), Some(60)));
+        text.insert_str(0, &"x\n".repeat(5));
+        assert_eq!(Modeline::parse(&text), modeline(Some("c"), Some(90)));
+    }
+}
@@ -0,0 +1,70 @@
+// -*- coding:utf-8-unix; -*-
+
+//! options.rs
+
+//  Copyright 2026 hanepjiv
+//  @author hanepjiv <hanepjiv@gmail.com>
+//  @copyright The MIT License (MIT) / Apache License Version 2.0
+//  @since 2026/10/18
+//  @date 2026/10/18
+
+// ////////////////////////////////////////////////////////////////////////////
+// use  =======================================================================
+use std::path::PathBuf;
+// ----------------------------------------------------------------------------
+use crate::flags::Flags;
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+/// struct Options
+///
+/// Command line overrides applied over the loaded configuration.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// language
+    pub language: Option<String>,
+    /// column
+    pub column: Option<usize>,
+    /// `separator_threshold`
+    pub separator_threshold: Option<usize>,
+    /// format
+    pub format: Option<String>,
+    /// backup
+    pub backup: Option<String>,
+    /// include
+    pub include: Vec<String>,
+    /// exclude
+    pub exclude: Vec<String>,
+    /// baseline
+    pub baseline: Option<PathBuf>,
+    /// `write_baseline`
+    pub write_baseline: Option<PathBuf>,
+    /// `changed_since`
+    pub changed_since: Option<String>,
+    /// jobs
+    pub jobs: Option<usize>,
+    /// `stdin_filename`, the path the standard input is checked as.
+    pub stdin_filename: Option<PathBuf>,
+    /// flags
+    pub flags: Flags,
+}
+// ============================================================================
+impl Default for Options {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            language: None,
+            column: None,
+            separator_threshold: None,
+            format: None,
+            backup: None,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            baseline: None,
+            write_baseline: None,
+            changed_since: None,
+            jobs: None,
+            stdin_filename: None,
+            flags: Flags::empty(),
+        }
+    }
+}
@@ -0,0 +1,308 @@
+// -*- coding:utf-8-unix; -*-
+
+//! reflow.rs
+
+//  Copyright 2026 hanepjiv
+//  @author hanepjiv <hanepjiv@gmail.com>
+//  @copyright The MIT License (MIT) / Apache License Version 2.0
+//  @since 2026/10/18
+//  @date 2026/10/18
+
+// ////////////////////////////////////////////////////////////////////////////
+// use  =======================================================================
+use core::ops::Range;
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+/// struct Line.
+///
+/// A comment line split into its prefix, the indentation, comment marker
+/// and spaces, its text and the end marker of a block comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Line {
+    /// prefix
+    pub prefix: String,
+    /// cont, the prefix of the lines continuing this one.
+    pub cont: String,
+    /// text
+    pub text: String,
+    /// foot
+    pub foot: String,
+}
+// ============================================================================
+impl Line {
+    // ========================================================================
+    /// new
+    ///
+    /// Chars of `marker` right after `head`, such as the last `/` of `///`,
+    /// belong to the prefix.
+    pub(crate) fn new(head: &str, body: &str, marker: &str) -> Self {
+        let rest = if head.ends_with(char::is_whitespace) {
+            body
+        } else {
+            body.trim_start_matches(|c| marker.contains(c))
+        };
+        let text = rest.trim_start();
+        let mut prefix = String::from(head);
+        prefix.push_str(&body[..body.len() - text.len()]);
+        Self {
+            cont: prefix.clone(),
+            prefix,
+            text: text.trim_end().to_owned(),
+            foot: String::new(),
+        }
+    }
+}
+// ============================================================================
+/// struct Paragraph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Paragraph {
+    /// range
+    pub range: Range<usize>,
+    /// fill, whether the lines may be rewrapped.
+    pub fill: bool,
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+/// bullet
+///
+/// The byte length of a list bullet and its spaces at the start of `text`.
+pub(crate) fn bullet(text: &str) -> Option<usize> {
+    let rest = text.strip_prefix(['-', '*', '+']).or_else(|| {
+        text.trim_start_matches(|c: char| c.is_ascii_digit())
+            .strip_prefix(['.', ')'])
+            .filter(|_| text.starts_with(|c: char| c.is_ascii_digit()))
+    })?;
+    let body = rest.trim_start_matches(' ');
+    (body.len() < rest.len() && !body.is_empty())
+        .then_some(text.len() - body.len())
+}
+// ----------------------------------------------------------------------------
+/// `is_fence`
+pub(crate) fn is_fence(text: &str) -> bool {
+    text.starts_with("```") || text.starts_with("~~~")
+}
+// ----------------------------------------------------------------------------
+/// `is_verbatim`
+///
+/// Empty lines, headings and table rows are never joined with others.
+fn is_verbatim(text: &str) -> bool {
+    text.is_empty()
+        || text.starts_with('#')
+        || (text.starts_with('|') && text.ends_with('|'))
+}
+// ============================================================================
+/// paragraphs
+///
+/// Splits `lines` into paragraphs.  A paragraph ends at an empty line, a
+/// list bullet, a change of prefix or the end of a block comment;
+/// continuation lines of a bullet are indented past it.  Fenced code and
+/// tags such as `@param` up to the next empty line are kept as is.
+pub(crate) fn paragraphs(lines: &[Line]) -> Vec<Paragraph> {
+    let mut ret = Vec::new();
+    let mut fence = false;
+    let mut tag = false;
+    let mut i = 0;
+    while let Some(l) = lines.get(i) {
+        let start = i;
+        i += 1;
+        if is_fence(&l.text) {
+            fence = !fence;
+        }
+        tag = !l.text.is_empty() && (tag || l.text.starts_with('@'));
+        if fence || tag || is_fence(&l.text) || is_verbatim(&l.text) {
+            ret.push(Paragraph {
+                range: start..i,
+                fill: false,
+            });
+            continue;
+        }
+        let mut cont = l.cont.clone();
+        cont.push_str(&" ".repeat(bullet(&l.text).unwrap_or(0)));
+        while let Some(n) = lines.get(i) {
+            if lines.get(i - 1).is_some_and(|x| !x.foot.is_empty())
+                || n.prefix != cont
+                || is_fence(&n.text)
+                || is_verbatim(&n.text)
+                || bullet(&n.text).is_some()
+            {
+                break;
+            }
+            i += 1;
+        }
+        ret.push(Paragraph {
+            range: start..i,
+            fill: true,
+        });
+    }
+    ret
+}
+// ----------------------------------------------------------------------------
+/// fill
+///
+/// Rewraps a paragraph at word boundaries so that each line fits `column`,
+/// the last one with the foot of the paragraph.  A word wider than the
+/// column is left on a line of its own.
+pub(crate) fn fill(
+    lines: &[Line],
+    column: usize,
+    width: &dyn Fn(&str) -> usize,
+) -> Vec<String> {
+    let Some(first) = lines.first() else {
+        return Vec::new();
+    };
+    let foot = lines.last().map_or("", |x| x.foot.as_str());
+    let b = bullet(&first.text).unwrap_or(0);
+    let mut rest = first.cont.clone();
+    rest.push_str(&" ".repeat(b));
+    let words = first.text[b..]
+        .split_whitespace()
+        .chain(lines.iter().skip(1).flat_map(|x| x.text.split_whitespace()));
+    let mut ret = Vec::new();
+    let mut cur = first.prefix.clone();
+    cur.push_str(&first.text[..b]);
+    let mut lead = cur.len();
+    let mut empty = true;
+    for w in words {
+        if !empty {
+            let next = format!("{cur} {w}");
+            if width(&next) <= column {
+                cur = next;
+                continue;
+            }
+            ret.push(core::mem::replace(&mut cur, rest.clone()));
+            lead = rest.len();
+        }
+        cur.push_str(w);
+        empty = false;
+    }
+    if column < width(&format!("{cur}{foot}"))
+        && let Some(i) = cur.rfind(' ').filter(|&i| lead <= i)
+    {
+        let mut next = rest.clone();
+        next.push_str(&cur[i + 1..]);
+        cur.truncate(i);
+        ret.push(core::mem::replace(&mut cur, next));
+    }
+    cur.push_str(foot);
+    ret.push(cur);
+    ret
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    // use  ===================================================================
+    use super::{Line, Paragraph, bullet, fill, paragraphs};
+    // ========================================================================
+    fn line(prefix: &str, text: &str) -> Line {
+        Line {
+            prefix: prefix.to_owned(),
+            cont: prefix.to_owned(),
+            text: text.to_owned(),
+            foot: String::new(),
+        }
+    }
+    // ========================================================================
+    #[test]
+    fn line_new() {
+        assert_eq!(
+            Line::new("    //", "/ doc", "/!"),
+            line("    /// ", "doc")
+        );
+        assert_eq!(Line::new("//", "! inner", "/!"), line("//! ", "inner"));
+        assert_eq!(Line::new("-- ", "- item", "-!"), line("-- ", "- item"));
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn bullets() {
+        assert_eq!(bullet("- a"), Some(2));
+        assert_eq!(bullet("12.  a"), Some(5));
+        assert_eq!(bullet("3) a"), Some(3));
+        assert_eq!(bullet("-a"), None);
+        assert_eq!(bullet(". a"), None);
+        assert_eq!(bullet("* "), None);
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn paragraph() {
+        let lines = [
+            line("// ", "one two"),
+            line("// ", "three"),
+            line("//", ""),
+            line("// ", "- item"),
+            line("//   ", "cont"),
+            line("// ", "```"),
+            line("//     ", "code"),
+            line("// ", "```"),
+            line("/// ", "doc"),
+            line("/// ", "| a | b |"),
+            line("-- ", "| haddock"),
+        ];
+        assert_eq!(
+            paragraphs(&lines),
+            [(0..2, true), (2..3, false), (3..5, true), (5..6, false)]
+                .into_iter()
+                .chain([(6..7, false), (7..8, false), (8..9, true)])
+                .chain([(9..10, false), (10..11, true)])
+                .map(|(range, fill)| Paragraph { range, fill })
+                .collect::<Vec<_>>()
+        );
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn paragraph_block() {
+        let mut first = line("/** ", "one");
+        first.cont = " * ".to_owned();
+        let mut end = line(" * ", "four");
+        end.foot = " */".to_owned();
+        let lines = [
+            first,
+            line(" * ", "two"),
+            line(" *", ""),
+            line(" * ", "@param x the"),
+            line(" *     ", "value"),
+            line(" *", ""),
+            end,
+            line("/* ", "five"),
+        ];
+        assert_eq!(
+            paragraphs(&lines),
+            [(0..2, true), (2..3, false), (3..4, false), (4..5, false)]
+                .into_iter()
+                .chain([(5..6, false), (6..7, true), (7..8, true)])
+                .map(|(range, fill)| Paragraph { range, fill })
+                .collect::<Vec<_>>()
+        );
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn fills() {
+        let width = |s: &str| s.chars().count();
+        assert_eq!(
+            fill(
+                &[line("  /// ", "aaa bbb ccc"), line("  /// ", "ddd")],
+                14,
+                &width
+            ),
+            ["  /// aaa bbb", "  /// ccc ddd"]
+        );
+        assert_eq!(
+            fill(&[line("# ", "- aaa bbb ccc")], 10, &width),
+            ["# - aaa", "#   bbb", "#   ccc"]
+        );
+        assert_eq!(
+            fill(&[line("// ", "aaaaaaaaaa b")], 8, &width),
+            ["// aaaaaaaaaa", "// b"]
+        );
+        let mut first = line("/** ", "aaa bbb");
+        first.cont = " * ".to_owned();
+        let mut last = line(" * ", "ccc ddd");
+        last.foot = " */".to_owned();
+        let block = [first, last];
+        assert_eq!(fill(&block, 14, &width), ["/** aaa bbb", " * ccc ddd */"]);
+        assert_eq!(
+            fill(&block, 12, &width),
+            ["/** aaa bbb", " * ccc", " * ddd */"]
+        );
+    }
+}
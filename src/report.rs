@@ -0,0 +1,422 @@
+// -*- coding:utf-8-unix; -*-
+
+//! report.rs
+
+//  Copyright 2026 hanepjiv
+//  @author hanepjiv <hanepjiv@gmail.com>
+//  @copyright The MIT License (MIT) / Apache License Version 2.0
+//  @since 2026/10/18
+//  @date 2026/10/18
+
+// ////////////////////////////////////////////////////////////////////////////
+// use  =======================================================================
+use std::{
+    io::Write as _,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+// ----------------------------------------------------------------------------
+use serde::Serialize;
+use serde_json::{Value, json};
+// ----------------------------------------------------------------------------
+use crate::error::Error;
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+/// enum Format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Format {
+    ///  Unknown.
+    Unknown,
+    ///  Text.
+    Text,
+    ///  Json.
+    Json,
+    ///  `JsonLines`.
+    JsonLines,
+    ///  Sarif.
+    Sarif,
+}
+// ============================================================================
+impl<'a> From<&'a str> for Format {
+    // ========================================================================
+    #[inline]
+    fn from(src: &'a str) -> Self {
+        match src.to_lowercase().as_str() {
+            "text" => Self::Text,
+            "json" => Self::Json,
+            "jsonl" | "json-lines" => Self::JsonLines,
+            "sarif" => Self::Sarif,
+            _ => Self::Unknown,
+        }
+    }
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+/// SARIF rules, one per `LineType` variant.
+const SARIF_RULES: [(&str, &str); 6] = [
+    ("other", "Code line exceeds the column limit."),
+    ("line_comment", "Line comment exceeds the column limit."),
+    (
+        "line_separator",
+        "Line separator does not fit the column limit.",
+    ),
+    (
+        "block_comment",
+        "Block comment does not fit the column limit.",
+    ),
+    (
+        "block_separator",
+        "Block separator does not fit the column limit.",
+    ),
+    (
+        "block_inner",
+        "Multi-line block comment exceeds the column limit.",
+    ),
+];
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+/// struct Violation.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Violation {
+    /// path
+    pub path: String,
+    /// row
+    pub row: usize,
+    /// width
+    pub width: usize,
+    /// column
+    pub column: usize,
+    /// `line_type`
+    pub line_type: &'static str,
+    /// language
+    pub language: String,
+    /// line
+    #[serde(skip)]
+    pub line: String,
+}
+// ============================================================================
+/// struct Summary.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Summary {
+    /// files
+    files: usize,
+    /// violations
+    violations: usize,
+}
+// ============================================================================
+impl Summary {
+    // ========================================================================
+    /// files
+    #[must_use]
+    #[inline]
+    pub const fn files(&self) -> usize {
+        self.files
+    }
+    // ------------------------------------------------------------------------
+    /// violations
+    #[must_use]
+    #[inline]
+    pub const fn violations(&self) -> usize {
+        self.violations
+    }
+}
+// ============================================================================
+/// enum Record.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record<'a> {
+    /// Violation.
+    Violation(&'a Violation),
+    /// Summary.
+    Summary(&'a Summary),
+}
+// ============================================================================
+/// struct Document.
+#[derive(Debug, Serialize)]
+struct Document<'a> {
+    /// violations
+    violations: &'a [Violation],
+    /// summary
+    summary: &'a Summary,
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+/// struct Reporter.
+#[derive(Debug)]
+pub(crate) struct Reporter {
+    /// format.
+    format: Format,
+    /// summary.
+    summary: Mutex<Summary>,
+    /// violations.
+    violations: Mutex<Vec<Violation>>,
+}
+// ============================================================================
+impl Reporter {
+    // ========================================================================
+    /// new.
+    pub(crate) const fn new(format: Format) -> Self {
+        Self {
+            format,
+            summary: Mutex::new(Summary {
+                files: 0,
+                violations: 0,
+            }),
+            violations: Mutex::new(Vec::new()),
+        }
+    }
+    // ========================================================================
+    /// file.
+    pub(crate) fn file(&self) {
+        let mut summary = self.summary();
+        summary.files = summary.files.saturating_add(1);
+    }
+    // ========================================================================
+    /// violation.
+    pub(crate) fn violation(&self, violation: Violation) -> Result<(), Error> {
+        {
+            let mut summary = self.summary();
+            summary.violations = summary.violations.saturating_add(1);
+        }
+        match self.format {
+            Format::Text => {
+                println!(
+                    "{0}({1}): {2} : {3}",
+                    violation.path,
+                    violation.row,
+                    violation.width,
+                    violation.line
+                );
+            }
+            Format::JsonLines => {
+                Self::println_json(&Record::Violation(&violation))?;
+            }
+            Format::Json | Format::Sarif => {
+                self.violations().push(violation);
+            }
+            Format::Unknown => {
+                return Err(Error::Inspect(format!(
+                    "::column79::report::Reporter::violation: \
+                     invalid format {:?}",
+                    self.format
+                )));
+            }
+        }
+        Ok(())
+    }
+    // ========================================================================
+    /// finish.
+    pub(crate) fn finish(&self) -> Result<Summary, Error> {
+        let summary = *self.summary();
+        match self.format {
+            Format::Text | Format::Unknown => Ok(()),
+            Format::JsonLines => {
+                Self::println_json(&Record::Summary(&summary))
+            }
+            Format::Json => Self::println_json(&Document {
+                violations: &self.violations(),
+                summary: &summary,
+            }),
+            Format::Sarif => {
+                Self::println_json(&Self::sarif(&self.violations()))
+            }
+        }?;
+        Ok(summary)
+    }
+    // ------------------------------------------------------------------------
+    /// summary.
+    fn summary(&self) -> MutexGuard<'_, Summary> {
+        self.summary.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+    // ------------------------------------------------------------------------
+    /// violations.
+    fn violations(&self) -> MutexGuard<'_, Vec<Violation>> {
+        self.violations
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+    // ========================================================================
+    /// sarif.
+    fn sarif(violations: &[Violation]) -> Value {
+        let rules = SARIF_RULES
+            .iter()
+            .map(|&(id, text)| {
+                json!({
+                    "id": format!("column79/{id}"),
+                    "name": id,
+                    "shortDescription": { "text": text },
+                    "defaultConfiguration": { "level": "warning" },
+                })
+            })
+            .collect::<Vec<_>>();
+        let results = violations
+            .iter()
+            .map(|v| {
+                json!({
+                    "ruleId": format!("column79/{0}", v.line_type),
+                    "ruleIndex": SARIF_RULES
+                        .iter()
+                        .position(|&(id, _)| id == v.line_type),
+                    "level": "warning",
+                    "message": {
+                        "text": format!(
+                            "{0}: width {1} does not fit column {2}.",
+                            v.language, v.width, v.column
+                        ),
+                    },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": {
+                                "uri": Self::sarif_uri(&v.path),
+                            },
+                            "region": { "startLine": v.row },
+                        },
+                    }],
+                    "properties": {
+                        "width": v.width,
+                        "column": v.column,
+                        "language": v.language,
+                    },
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                        "rules": rules,
+                    },
+                },
+                "columnKind": "unicodeCodePoints",
+                "results": results,
+            }],
+        })
+    }
+    // ------------------------------------------------------------------------
+    /// `sarif_uri`.
+    fn sarif_uri(path: &str) -> String {
+        let mut uri = String::with_capacity(path.len());
+        if path.starts_with('/') {
+            uri.push_str("file://");
+        }
+        for c in path.chars() {
+            match c {
+                '\\' => uri.push('/'),
+                '%' => uri.push_str("%25"),
+                ' ' => uri.push_str("%20"),
+                '#' => uri.push_str("%23"),
+                '?' => uri.push_str("%3F"),
+                _ => uri.push(c),
+            }
+        }
+        uri
+    }
+    // ========================================================================
+    /// `println_json`.
+    fn println_json(value: &impl Serialize) -> Result<(), Error> {
+        let mut out = std::io::stdout().lock();
+        serde_json::to_writer(&mut out, value)?;
+        out.write_all(b"\n")?;
+        Ok(())
+    }
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    // use  ===================================================================
+    use serde_json::json;
+    // ------------------------------------------------------------------------
+    use super::{Document, Record, Reporter, SARIF_RULES, Summary, Violation};
+    // ========================================================================
+    fn violation(path: &str, line_type: &'static str) -> Violation {
+        Violation {
+            path: path.to_owned(),
+            row: 3,
+            width: 90,
+            column: 79,
+            line_type,
+            language: "rust".to_owned(),
+            line: "x".repeat(90),
+        }
+    }
+    // ========================================================================
+    #[test]
+    fn sarif() {
+        let violations = SARIF_RULES
+            .iter()
+            .map(|&(id, _)| violation("src/a.rs", id))
+            .collect::<Vec<_>>();
+        let value = Reporter::sarif(&violations);
+        let run = &value["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().expect("rules");
+        assert_eq!(rules.len(), SARIF_RULES.len());
+        let results = run["results"].as_array().expect("results");
+        assert_eq!(results.len(), SARIF_RULES.len());
+        for (i, (result, &(id, _))) in
+            results.iter().zip(SARIF_RULES.iter()).enumerate()
+        {
+            assert_eq!(result["ruleIndex"], json!(i));
+            assert_eq!(result["ruleId"], json!(format!("column79/{id}")));
+            assert_eq!(rules[i]["id"], result["ruleId"]);
+            let location = &result["locations"][0]["physicalLocation"];
+            assert_eq!(location["region"]["startLine"], json!(3));
+        }
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn sarif_uri() {
+        assert_eq!(Reporter::sarif_uri("src/a.rs"), "src/a.rs");
+        assert_eq!(Reporter::sarif_uri("/tmp/a b.rs"), "file:///tmp/a%20b.rs");
+        assert_eq!(Reporter::sarif_uri(r"src\x#1?.rs"), "src/x%231%3F.rs");
+        assert_eq!(Reporter::sarif_uri("100%.rs"), "100%25.rs");
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn json() {
+        let violations = [violation("src/a.rs", "line_comment")];
+        let summary = Summary {
+            files: 2,
+            violations: 1,
+        };
+        let expected = json!({
+            "path": "src/a.rs",
+            "row": 3,
+            "width": 90,
+            "column": 79,
+            "line_type": "line_comment",
+            "language": "rust",
+        });
+        assert_eq!(
+            serde_json::to_value(Document {
+                violations: &violations,
+                summary: &summary,
+            })
+            .expect("document"),
+            json!({
+                "violations": [expected],
+                "summary": { "files": 2, "violations": 1 },
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(Record::Summary(&summary)).expect("summary"),
+            json!({ "type": "summary", "files": 2, "violations": 1 })
+        );
+        let mut record = expected;
+        drop(
+            record
+                .as_object_mut()
+                .expect("object")
+                .insert("type".to_owned(), json!("violation")),
+        );
+        assert_eq!(
+            serde_json::to_value(Record::Violation(&violations[0]))
+                .expect("violation"),
+            record
+        );
+    }
+}
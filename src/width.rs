@@ -0,0 +1,95 @@
+// -*- coding:utf-8-unix; -*-
+
+//! width.rs
+
+//  Copyright 2026 hanepjiv
+//  @author hanepjiv <hanepjiv@gmail.com>
+//  @copyright The MIT License (MIT) / Apache License Version 2.0
+//  @since 2026/10/18
+//  @date 2026/10/18
+
+// ////////////////////////////////////////////////////////////////////////////
+// use  =======================================================================
+use unicode_segmentation::UnicodeSegmentation as _;
+use unicode_width::UnicodeWidthStr as _;
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+/// enum `WidthMode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WidthMode {
+    ///  Unknown.
+    Unknown,
+    ///  Graphemes, extended grapheme clusters.
+    Graphemes,
+    ///  Display, Unicode East Asian Width.
+    Display,
+    ///  Bytes, UTF-8 bytes.
+    Bytes,
+    ///  Chars, Unicode scalar values.
+    Chars,
+}
+// ============================================================================
+impl<'a> From<&'a str> for WidthMode {
+    // ========================================================================
+    #[inline]
+    fn from(src: &'a str) -> Self {
+        match src.to_lowercase().as_str() {
+            "graphemes" => Self::Graphemes,
+            "display" => Self::Display,
+            "bytes" => Self::Bytes,
+            "chars" => Self::Chars,
+            _ => Self::Unknown,
+        }
+    }
+}
+// ============================================================================
+impl WidthMode {
+    // ========================================================================
+    /// width
+    ///
+    /// A tab advances to the next multiple of `tab_width`.
+    #[expect(clippy::arithmetic_side_effects, reason = "checked")]
+    pub(crate) fn width(self, tab_width: usize, s: &str) -> usize {
+        let tab_width = tab_width.max(1);
+        let mut segments = s.split('\t');
+        let mut ret = segments.next().map_or(0, |x| self.width_segment(x));
+        for i in segments {
+            ret = (ret / tab_width + 1) * tab_width + self.width_segment(i);
+        }
+        ret
+    }
+    // ------------------------------------------------------------------------
+    /// `width_segment`
+    fn width_segment(self, s: &str) -> usize {
+        match self {
+            Self::Graphemes | Self::Unknown => s.graphemes(true).count(),
+            Self::Display => s.width(),
+            Self::Bytes => s.len(),
+            Self::Chars => s.chars().count(),
+        }
+    }
+}
+// ////////////////////////////////////////////////////////////////////////////
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    // use  ===================================================================
+    use super::WidthMode;
+    // ========================================================================
+    #[test]
+    fn width() {
+        let s = "a\u{65e5}\u{672c}e\u{301}";
+        assert_eq!(WidthMode::Graphemes.width(8, s), 4);
+        assert_eq!(WidthMode::Display.width(8, s), 6);
+        assert_eq!(WidthMode::Bytes.width(8, s), 10);
+        assert_eq!(WidthMode::Chars.width(8, s), 5);
+    }
+    // ------------------------------------------------------------------------
+    #[test]
+    fn width_tab() {
+        assert_eq!(WidthMode::Graphemes.width(8, "\tx"), 9);
+        assert_eq!(WidthMode::Graphemes.width(8, "abc\tx"), 9);
+        assert_eq!(WidthMode::Graphemes.width(4, "abcd\t\t"), 12);
+        assert_eq!(WidthMode::Display.width(4, "\u{65e5}\t"), 4);
+    }
+}